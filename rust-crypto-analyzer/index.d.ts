@@ -7,8 +7,27 @@ export interface PasswordAnalysis {
   isCompliant: boolean
   strengthScore: number
   entropyBits: number
+  /** `entropy_bits` discounted for detected low-entropy constructions */
+  effectiveEntropyBits: number
+  /**
+   * Alternate estimate from the observed character distribution: length times log2 of
+   * `effective_alphabet_size`, so skewed passwords like "aaaab" rate low whatever their classes
+   */
+  distributionEntropyBits: number
   patternAnalysis: PatternAnalysis
+  /** Common padding token (e.g. "!!!", "123") found at the start or end */
+  paddingToken?: string
+  /** Strength score of the password with `padding_token` removed */
+  baseStrengthScore?: number
+  /** Messages of `feedback_items`, in the same order */
   feedback: Array<string>
+  /** Critical items first, then warnings, then info */
+  feedbackItems: Array<FeedbackItem>
+  /**
+   * SHA-256 hex of every other field except `analysis_time_ms`, so identical results hash
+   * identically and a change in behavior for the same input changes the hash
+   */
+  resultHash: string
   analysisTimeMs: number
 }
 export interface PatternAnalysis {
@@ -16,13 +35,537 @@ export interface PatternAnalysis {
   hasLowercase: boolean
   hasNumbers: boolean
   hasSymbols: boolean
+  /** Any character outside ASCII, like an accented letter or an emoji */
+  hasNonAscii: boolean
   length: number
+  /** `length` holds the `bucket_length` bucket index rather than the length */
+  lengthBucketed: boolean
   repeatedChars: number
   sequentialChars: number
+  longestClassRun: number
+  /** Password is an identity term reversed or rotated (e.g. "htimsj" for "jsmith") */
+  identityTransformed: boolean
+  /**
+   * Contains the user's `date_of_birth` as MMDD, DDMM, YYYY, an 8-digit or a 2-digit-year form;
+   * only checked when a user context with a birth date is supplied
+   */
+  containsDob: boolean
+  /** Zero-width characters, a BOM or trailing control characters were stripped before analysis */
+  hadPasteArtifacts: boolean
+  /** A short unit of alternating character classes repeated, like "a1a1a1a1" or "Z!Z!Z!" */
+  alternatingPattern: boolean
+  /** Letters and digits interleaved with both advancing in step, like "a1b2c3" or "3c2b1a" */
+  interleavedSequence: boolean
+  /** The only uppercase letter is the leading one, as in "Password1!" */
+  onlyFirstCapitalized: boolean
+  /** Letters strictly alternate case, as in "pAsSwOrD" */
+  studlyCaps: boolean
+  /** A straight-line keyboard walk typed partly with shift held, like "!QAZ2wsx" or "@WSX#EDC" */
+  shiftKeyboardWalk: boolean
+  /** Built around a popular given name, allowing for case, leet and digit/symbol affixes ("j3ssica1") */
+  containsCommonName: boolean
+  /** A well-known phrase typed without its spaces, like "correcthorsebatterystaple" */
+  concatenatedPhrase: boolean
+  /** The common password that leet substitutions resolve to ("password" for "p@ssw0rd") */
+  leetCommonBase?: string
+  /** First of the policy's `banned_sequences` found, as written in the policy */
+  containsBannedSequence?: string
+  /** Contains a term from the list loaded with `load_forbidden_substrings` */
+  containsForbidden: boolean
+  /** The forbidden term that matched, as it was loaded */
+  forbiddenMatch?: string
 }
-export declare function checkPasswordPolicy(password: string): PasswordAnalysis
+export const enum Severity {
+  /** Fails compliance */
+  Critical = 'Critical',
+  Warning = 'Warning',
+  Info = 'Info'
+}
+export interface FeedbackItem {
+  code: string
+  severity: Severity
+  message: string
+}
+export interface PasswordPolicy {
+  minLength?: number
+  minStrengthScore?: number
+  /** Longest allowed run of one character class (e.g. 5 digits in a row), unlimited when unset */
+  maxClassRun?: number
+  /** Requires a symbol that is neither the first nor the last character */
+  requireInteriorSymbol?: boolean
+  /** Lets long digit-only passwords (PIN-like flows) pass on length and patterns instead of score */
+  allowNumericOnly?: boolean
+  /** Rejects passwords containing the current calendar year (e.g. "Summer2026!") */
+  forbidCurrentYear?: boolean
+  /** Leading characters (e.g. a mandated department code) left out of sequence/repeat/keyboard checks */
+  ignoreFirstChars?: number
+  /** Trailing characters left out of sequence/repeat/keyboard checks */
+  ignoreLastChars?: number
+  /** Minimum estimated time (seconds) to crack offline against a fast hash at 10^10 guesses/s */
+  minOfflineCrackSeconds?: number
+  /** Rejects passwords shaped like an email address (often the user's own login) */
+  forbidEmailFormat?: boolean
+  /** Overrides the built-in scoring model's mode, e.g. `Entropy` for scores that never drop as entropy rises */
+  scoringMode?: ScoringMode
+  /**
+   * Org-specific sequences the password may not contain, case-insensitive. `#` matches any
+   * digit and `?` any single character, e.g. "SKU-####"
+   */
+  bannedSequences?: Array<string>
+  /**
+   * Report `PatternAnalysis.length` as a bucket index instead of the exact length (0 for 1-7,
+   * 1 for 8-11, 2 for 12-15, 3 for 16+) and set `length_bucketed`. Checks and scoring still use
+   * the true length, and functions that take an analysis back honor the flag
+   */
+  bucketLength?: boolean
+  /**
+   * Fewest character edits (insertions, deletions, substitutions) a new password must be from
+   * each previous one, enforced by `check_edit_distance_from_previous` at change time
+   */
+  minEditDistanceFromPrevious?: number
+  /** Suggests adding a non-ASCII character (accented letter, emoji, ...) to pure-ASCII passwords */
+  encourageNonAscii?: boolean
+  /** Rejects any non-ASCII character, for systems that can't store Unicode */
+  asciiOnly?: boolean
+}
+export const enum EditKind {
+  AppendChars = 'AppendChars',
+  AddUppercase = 'AddUppercase',
+  AddLowercase = 'AddLowercase',
+  AddNumber = 'AddNumber',
+  AddSymbol = 'AddSymbol',
+  BreakSequence = 'BreakSequence',
+  InsertSymbol = 'InsertSymbol'
+}
+/**
+ * One step of a compliance plan. Additions are appended to the end of the password,
+ * `BreakSequence` and `InsertSymbol` insert a symbol before the character at `position`
+ */
+export interface EditStep {
+  kind: EditKind
+  /** Number of characters to append, only set for `AppendChars` */
+  count?: number
+  /** Character index (in the password as edited so far), only set for insertions */
+  position?: number
+}
+export interface UserContext {
+  username?: string
+  email?: string
+  fullName?: string
+  /** YYYY-MM-DD */
+  dateOfBirth?: string
+  /** Names of the service or company the password is for */
+  serviceTerms?: Array<string>
+}
+export interface PolicyColumns {
+  scores: Array<number>
+  compliant: Array<boolean>
+  /** `entropy_bits` per password */
+  entropy: Array<number>
+  /** `PatternAnalysis.length` per password, the bucket index when the policy sets `bucket_length` */
+  length: Array<number>
+}
+export interface PolicyRelaxation {
+  alreadyCompliant: boolean
+  relaxations: Array<ConstraintRelaxation>
+}
+export interface ConstraintRelaxation {
+  /** Policy field as passed from JS, e.g. "minLength" */
+  constraint: string
+  /** Setting the password would pass with; 0/1 for switches turned off/on */
+  relaxedValue: number
+  message: string
+}
+export interface VerifyPepperResult {
+  valid: boolean
+  /** Index into the supplied peppers of the one that verified */
+  pepperIndex?: number
+}
+export interface MigrateResult {
+  valid: boolean
+  /** Hash in the target algorithm, only set when valid and the stored hash used another one */
+  newHash?: string
+}
+export interface BreachInfo {
+  count: number
+  mostRecentYear?: number
+}
+export interface RiskConfig {
+  /**
+   * How far a breach pushes risk, 0 to 1 (default 0.5). 0 ignores breaches, 0.5 scales the push
+   * by sighting count and recency, 1 makes any breach maximum risk; values between interpolate
+   */
+  breachWeight?: number
+}
+export interface TelemetryRecord {
+  compliant: boolean
+  /** `strength_score` rounded down to a multiple of 20 */
+  scoreBand: number
+  /** `effective_entropy_bits` rounded down to a multiple of 20, capped at 100 */
+  entropyBucket: number
+  /** "<8", "8-11", "12-15" or "16+" */
+  lengthBucket: string
+  /** Character classes present, 0 to 4 */
+  classCount: number
+  /** Common password, phrase or name, including leet forms */
+  commonWord: boolean
+  tooShort: boolean
+  /** Character sequences or keyboard walks */
+  sequential: boolean
+  /** Repeated characters or a repeated alternating unit */
+  repeated: boolean
+  /** Built from the user's own details */
+  personalInfo: boolean
+  /** First-letter-only or alternating capitalization */
+  predictableCase: boolean
+  /** Matched the loaded forbidden-substring list */
+  blocklisted: boolean
+  /** Below the policy's strength score or crack-time minimum */
+  lowStrength: boolean
+}
+export interface RiskAssessment {
+  /** 0 (low risk) to 100 (high risk) */
+  riskScore: number
+  /** 1.0 for a breach this year, halving every 4 years after, 0 when not breached */
+  breachRecencyWeight: number
+}
+export interface WeakEntry {
+  /** Position in the input batch */
+  index: number
+  strengthScore: number
+  /** Code of the primary weakness (see `primary_weakness_message`), none for strong passwords */
+  topReason?: string
+}
+export interface PolicyImpact {
+  /** Compliant under the old policy, not under the new one */
+  newlyFailing: number
+  /** Non-compliant under the old policy, compliant under the new one */
+  newlyPassing: number
+  unchanged: number
+}
+export interface PassphraseAnalysis {
+  wordCount: number
+  /** Words counted once however often they repeat, compared case-insensitively */
+  distinctWordCount: number
+  /** Lowercased words that appear more than once, in order of first appearance */
+  repeatedWords: Array<string>
+  /** From distinct words only, each assumed drawn from a diceware-sized (7,776 word) list */
+  entropyBits: number
+  /** At least `min_words` distinct words */
+  isCompliant: boolean
+  feedbackItems: Array<FeedbackItem>
+}
+export interface FingerprintBatch {
+  /** SHA-256 hex per input, in input order */
+  fingerprints: Array<string>
+  distinctCount: number
+  /** Occurrences of the most reused fingerprint, 0 for an empty batch */
+  maxReuseCount: number
+}
+export interface ReuseCluster {
+  /** SHA-256 hex shared by every member */
+  fingerprint: string
+  /** Input positions, ascending */
+  memberIndices: Array<number>
+  count: number
+}
+export interface ResourceInfo {
+  name: string
+  entryCount: number
+  /** Crate version the list shipped with */
+  version: string
+  /** SHA-256 hex of the entries joined with newlines, in their embedded order */
+  sha256: string
+}
+export interface AnalyzeAndHashResult {
+  analysis: PasswordAnalysis
+  /** Argon2 PHC string, none when the password is non-compliant */
+  hash?: string
+}
+export interface ScoringModel {
+  version: number
+  mode: ScoringMode
+  /** Effective entropy that earns `max_score` in `Entropy` mode */
+  entropyTargetBits: number
+  /** Points for the highest tier whose `min_length` the password reaches */
+  lengthTiers: Array<LengthTier>
+  lowercaseBonus: number
+  uppercaseBonus: number
+  /** Replaces `uppercase_bonus` when the only capital is the first letter */
+  predictableUppercaseBonus: number
+  /** Replaces `lowercase_bonus` + `uppercase_bonus` when letters strictly alternate case */
+  studlyCaseBonus: number
+  numberBonus: number
+  symbolBonus: number
+  repeatPenalty: number
+  sequencePenalty: number
+  shiftWalkPenalty: number
+  maxScore: number
+}
+export const enum ScoringMode {
+  /** Length tier plus a bonus per character class, minus pattern penalties */
+  ClassBonus = 'ClassBonus',
+  /**
+   * `effective_entropy_bits / entropy_target_bits * max_score`, clamped to `max_score`.
+   * More entropy never scores lower; class bonuses, tiers and penalties are ignored
+   */
+  Entropy = 'Entropy'
+}
+export interface LengthTier {
+  minLength: number
+  points: number
+}
+export interface VerifiedPassword {
+  password: string
+}
+export interface Argon2Params {
+  memoryKib: number
+  iterations: number
+  parallelism: number
+}
+export interface BcryptParams {
+  cost: number
+}
+export declare function checkPasswordPolicy(password: string, policy?: PasswordPolicy | undefined | null, identityTerms?: Array<string> | undefined | null): PasswordAnalysis
+/**
+ * Same as `check_password_policy` but `analysis_time_ms` is always 0, so identical inputs
+ * give identical results (for equality assertions in tests)
+ */
+export declare function checkPasswordPolicyStable(password: string, policy?: PasswordPolicy | undefined | null, identityTerms?: Array<string> | undefined | null): PasswordAnalysis
+/**
+ * Single signup entrypoint: runs every context-dependent check against `context` on top of the
+ * policy. Username, email (whole and local part), name parts and service terms may not appear in
+ * the password, nor may the date of birth written as a date; the identity terms also feed the
+ * reversal/rotation check. Fails when `date_of_birth` isn't YYYY-MM-DD
+ */
+export declare function checkPasswordPolicyWithContext(password: string, policy: PasswordPolicy | undefined | null, context: UserContext): PasswordAnalysis
+/**
+ * `check_password_policy` with feedback messages in `locale` ("en" or "es", region suffixes like
+ * "es-MX" accepted). Codes and severities are language-independent, so the UI gets the stable
+ * code and the translated text in one payload. Fails on an unsupported locale
+ */
+export declare function checkPasswordPolicyLocalized(password: string, policy: PasswordPolicy | undefined | null, locale: string): PasswordAnalysis
+/**
+ * Passphrase check: words are split on whitespace, '-', '_' and '.', and repeats count once,
+ * so "apple apple apple orange" has 2 distinct words and the entropy of 2. Non-compliant when
+ * there are fewer than `min_words` (default 4) distinct words
+ */
+export declare function analyzePassphrase(passphrase: string, minWords?: number | undefined | null): PassphraseAnalysis
+/**
+ * Registration path: analyzes and Argon2-hashes the password off the JS thread, running both
+ * at once on the rayon pool. The hash is computed speculatively and discarded (zeroized) when
+ * the password turns out non-compliant, so `hash` is only set for compliant passwords
+ */
+export declare function analyzeAndHashParallelAsync(password: string, policy?: PasswordPolicy | undefined | null): Promise<AnalyzeAndHashResult>
+/**
+ * Replaces the global forbidden-substring list used by every policy check. Terms are compiled
+ * into one Aho-Corasick automaton so large lists stay fast, and matching is case-insensitive.
+ * Loading an empty list turns the check off
+ */
+export declare function loadForbiddenSubstrings(terms: Array<string>): void
+export declare function analyzeBatch(passwords: Array<string>, policy?: PasswordPolicy | undefined | null, chunkSize?: number | undefined | null): Array<PasswordAnalysis>
+/**
+ * Batch analysis as parallel columns (index i of every column is input i), much cheaper to
+ * marshal than full analyses and ready for charting
+ */
+export declare function batchPolicyColumns(passwords: Array<string>, policy: PasswordPolicy): PolicyColumns
+/**
+ * Lists the ordered edits that, applied one after another, make the password compliant
+ * with `policy`. Returns an empty plan when the password already complies. Violations that
+ * appending/inserting can't fix (a forbidden term, the current year) are left in place
+ */
+export declare function compliancePlan(password: string, policy: PasswordPolicy): Array<EditStep>
+/**
+ * For "why can't I use this" messages: each single policy constraint that, relaxed on its own,
+ * would make the password compliant, with the loosest setting that lets it pass. Empty when
+ * no one constraint is the blocker (e.g. a common password fails regardless of policy)
+ */
+export declare function minimumRelaxation(password: string, policy: PasswordPolicy): PolicyRelaxation
+/**
+ * Deterministic policy-compliant code for reproducible provisioning (e.g. recovery codes):
+ * the same `seed` and policy always give the same output. NOT for user passwords, anyone holding
+ * the seed can regenerate the code, so the seed must be kept as secret as the code itself.
+ * Seeds shorter than 16 bytes are rejected
+ */
+export declare function generateFromSeed(seed: Array<number>, policy: PasswordPolicy): string
+/** The scoring constants currently in use, serialize it to pin them for `score_with_model_json` */
+export declare function scoringModel(): ScoringModel
+/**
+ * Name, size, version and content hash of every list compiled into the library, to check the
+ * built-in data is what's expected and spot changes across upgrades
+ */
+export declare function embeddedResources(): Array<ResourceInfo>
+/**
+ * Scores a password with a `ScoringModel` given as JSON (e.g. a pinned `scoring_model()` export)
+ * instead of the built-in one. A JSON missing a field or pinned at another `version` is rejected,
+ * so an upgrade that changes scoring surfaces as an error instead of silently mixing models
+ */
+export declare function scoreWithModelJson(password: string, modelJson: string): number
+/**
+ * Projected strength score if `additional_chars` random characters from the password's current
+ * character classes were appended, for "keep typing" hints. Random additions bring no new
+ * classes or patterns, so only length-driven scoring moves (the length tier, or entropy in `Entropy` mode)
+ */
+export declare function scoreIfExtended(password: string, additionalChars: number, policy: PasswordPolicy): number
+/**
+ * Effective number of equally likely symbols the password draws from: the perplexity (2 to the
+ * Shannon entropy) of its character distribution. Eight distinct characters give 8, while a
+ * skewed "aaaab" gives about 1.65 despite using two symbols. 0 for an empty password
+ */
+export declare function effectiveAlphabetSize(password: string): number
+/**
+ * Per grapheme, how much it adds to predictability from 0 (random-looking) to 1, for coloring
+ * each character in a strength visualization. Uses zxcvbn's matched spans (dictionary words,
+ * sequences, keyboard patterns, repeats, dates): a span scores 1 minus its guess bits per
+ * character relative to a random printable character, unmatched characters score 0.
+ * zxcvbn only evaluates the first 100 characters, so graphemes past that always score 0
+ */
+export declare function charGuessability(password: string): Array<number>
+/**
+ * Checks the blocklist through combinations of reversal, digit/symbol affix stripping and leet
+ * normalization, returning the common base word that matched. Catches compound evasions like
+ * "1drowssap" (reversed "password1"). At most 12 candidate forms are tried per password
+ */
+export declare function normalizedCommonMatch(password: string): string | null
+/**
+ * Password-change check for lazy reuse the stored hash can't catch: true when `candidate` is
+ * `previous` with letter case changed and nothing else, from a full inversion ("Secret" ->
+ * "sECRET") down to a single letter. An identical password is not a case change
+ */
+export declare function isCaseInversion(previous: string, candidate: string): boolean
+/**
+ * Password-change check against the user's previous plaintexts (only while they are transiently
+ * available): false when `candidate` is fewer than the policy's `min_edit_distance_from_previous`
+ * edits from any of them. Fails when the policy doesn't set the minimum
+ */
+export declare function checkEditDistanceFromPrevious(candidate: string, previousPlaintexts: Array<string>, policy: PasswordPolicy): boolean
+/**
+ * Counts, across a batch, how many passwords failed each policy rule (by critical feedback code),
+ * for reporting which rules trip signups most. Compliant passwords add to no bucket
+ */
+export declare function failureReasonHistogram(passwords: Array<string>, policy: PasswordPolicy): Record<string, number>
+/**
+ * Dry-runs a policy change over a batch of plaintexts (audit environments only): how many would
+ * start failing, start passing, or keep their compliance under `new_policy` versus `old_policy`
+ */
+export declare function policyImpact(passwords: Array<string>, oldPolicy: PasswordPolicy, newPolicy: PasswordPolicy): PolicyImpact
+/**
+ * Analyzes the batch in parallel (default policy) and returns the `k` weakest entries in
+ * ascending `strength_score` order, ties kept in input order. Entries carry the input index,
+ * never the plaintext, so the result can go to ops for account outreach
+ */
+export declare function weakestPasswords(passwords: Array<string>, k: number): Array<WeakEntry>
+/**
+ * Picks the single most important weakness for a one-line summary, in priority order:
+ * breached > common > too short > single character > sequential > low entropy > missing class.
+ * Breach status isn't part of the analysis, pass the HIBP count as `breach_count`
+ */
+export declare function primaryWeaknessMessage(analysis: PasswordAnalysis, breachCount?: number | undefined | null): string | null
+/**
+ * Combines the strength analysis with breach exposure into a 0-100 risk score. Breaches push
+ * the score towards 100, scaled by how many times the password was seen and how recently, and
+ * by `config.breach_weight`
+ */
+export declare function assessRisk(analysis: PasswordAnalysis, breach?: BreachInfo | undefined | null, config?: RiskConfig | undefined | null): RiskAssessment
+/**
+ * Coarse, non-reversible summary of an analysis for product analytics: bands and buckets
+ * instead of exact values, and weakness categories as booleans. Nothing in it (no exact length,
+ * substrings, matched terms or hashes) can be turned back into the password
+ */
+export declare function telemetryRecord(analysis: PasswordAnalysis): TelemetryRecord
 /** Hashes a password securely using Argon2 */
 export declare function hashPassword(password: string): string
+/**
+ * Hashes with explicit Argon2id `params` and a random salt of `salt_len` bytes (default 16,
+ * allowed 8 to 48). Verification reads params and salt back from the PHC string, so
+ * `verify_password_hash` works unchanged
+ */
+export declare function hashPasswordWithParams(password: string, params: Argon2Params, saltLen?: number | undefined | null): string
 export declare function verifyPasswordHash(password: string, hash: string): boolean
-export declare function batchHashPasswords(passwords: Array<string>): Record<string, string>
+/**
+ * Normalizes the password to `form` ("NFC" by default, or "NFKC") before Argon2 hashing and
+ * prefixes the PHC string with the form used, e.g. "nfc$$argon2id$...". The marker isn't secret;
+ * it pins the form for verification even if the default changes later
+ */
+export declare function hashPasswordNormalized(password: string, form?: string | undefined | null): string
+/**
+ * Verifies against a `hash_password_normalized` hash, normalizing with the form its marker names.
+ * Hashes without a marker are verified against the password as given. Fails on an unknown marker
+ */
+export declare function verifyPasswordNormalized(password: string, storedHash: string): boolean
+/**
+ * `verify_password_hash` padded with a sleep so the call takes at least `min_duration_ms`
+ * whether the password matches, mismatches or the hash is malformed. A verification that
+ * already ran past the envelope resolves straight away. Verification and the wait both run off
+ * the JS thread
+ */
+export declare function verifyPasswordFixedTime(password: string, hash: string, minDurationMs: number): Promise<boolean>
+/**
+ * True when a hash created at `created_at_ms` (Unix epoch millis) is more than `max_age_days`
+ * old, so callers can force a rehash on the next login even when its params are current.
+ * Timestamps in the future are never stale
+ */
+export declare function isHashStale(createdAtMs: number, maxAgeDays: number): boolean
+/**
+ * Hashes a password with Argon2, feeding `pepper` in as Argon2's secret key input.
+ * The pepper is never stored in the PHC string, so the same pepper is needed to verify
+ */
+export declare function hashPasswordWithPepper(password: string, pepper: Array<number>): string
+/**
+ * Verifies against a peppered hash by trying each candidate pepper in order, for rotating
+ * peppers on hashes that don't record which pepper produced them
+ */
+export declare function verifyWithPepperRotation(password: string, hash: string, peppers: Array<Array<number>>): VerifyPepperResult
+export declare function batchHashPasswords(passwords: Array<string>, chunkSize?: number | undefined | null): Record<string, string>
+/**
+ * Unkeyed SHA-256 (hex) of every password in input order, plus how many distinct fingerprints
+ * the batch has and how often the most reused one appears, for dedup and reuse audits over an
+ * export. Unsalted, so keep the output as sensitive as the passwords; see `reuse_fingerprint`
+ * for a keyed variant
+ */
+export declare function batchFingerprintSha256(passwords: Array<string>): FingerprintBatch
+/**
+ * Groups an export's passwords by SHA-256 fingerprint and returns the groups shared by two or
+ * more inputs (e.g. a default password set on many accounts), ordered by their first member.
+ * Like `batch_fingerprint_sha256` the fingerprints are unsalted, so treat them as sensitive
+ */
+export declare function findReuseClusters(passwords: Array<string>): Array<ReuseCluster>
+/**
+ * Rehashes already-verified plaintexts into `algorithm` ("argon2" or "bcrypt") for bulk migrations.
+ * `params_json` holds `Argon2Params` or `BcryptParams` (empty for defaults). Output order matches
+ * the input, and each plaintext is zeroized once hashed
+ */
+export declare function batchRehash(pairs: Array<VerifiedPassword>, algorithm: string, paramsJson: string): Array<string>
+/**
+ * Verifies against a stored Argon2 or bcrypt hash (detected from its prefix) and, when it
+ * matches but was made with a different algorithm than `target_algo`, returns a replacement hash
+ * in the target algorithm (`target_params_json` as for `batch_rehash`) to store instead. Drives
+ * an algorithm migration one login at a time; params of a same-algorithm hash aren't compared
+ */
+export declare function verifyAndMigrateAlgo(password: string, storedHash: string, targetAlgo: string, targetParamsJson: string): MigrateResult
+/**
+ * Returns the per-hash Argon2 memory cost (KiB) that keeps `max_concurrent` simultaneous
+ * hashes within `total_ram_mib`. The budget is divided by `safety_factor` (values below 1
+ * are treated as 1) to leave headroom, and the result is kept within Argon2's memory limits.
+ */
+export declare function memoryPerHashForBudget(totalRamMib: number, maxConcurrent: number, safetyFactor: number): number
+/**
+ * Relative cost of an Argon2 profile, for "B is 2.3x A" comparisons without benchmarking:
+ * memory (MiB) x iterations, i.e. MiB of memory filled per hash. Parallelism isn't a factor, lanes
+ * split the same memory so total CPU work is unchanged (only wall time drops given free cores)
+ */
+export declare function argon2WorkUnits(params: Argon2Params): number
+/**
+ * Keyed fingerprint for spotting the same password across sibling services: HMAC-SHA256 of the
+ * password under the shared `app_salt`, hex encoded. Not a storage hash (no work factor), and
+ * irreversible without the key, so fingerprints are only comparable between services sharing it
+ */
+export declare function reuseFingerprint(password: string, appSalt: Array<number>): string
+/**
+ * Lookup key for our internal breach corpus: HMAC-SHA256 of the password under `corpus_key`,
+ * hex encoded, so the corpus is stored keyed by the same HMAC rather than a plaintext-derived hash.
+ * Keep `corpus_key` in the secrets store and never reuse the reuse-fingerprint salt; rotating it
+ * means re-keying the whole corpus, since old keys no longer match
+ */
+export declare function breachLookupKey(password: string, corpusKey: Array<number>): string
 export declare function hashPasswordSha1(password: string): string
@@ -310,10 +310,54 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { checkPasswordPolicy, hashPassword, verifyPasswordHash, batchHashPasswords, hashPasswordSha1 } = nativeBinding
+const { Severity, EditKind, ScoringMode, checkPasswordPolicy, checkPasswordPolicyStable, checkPasswordPolicyWithContext, checkPasswordPolicyLocalized, analyzePassphrase, analyzeAndHashParallelAsync, loadForbiddenSubstrings, analyzeBatch, batchPolicyColumns, compliancePlan, minimumRelaxation, generateFromSeed, scoringModel, embeddedResources, scoreWithModelJson, scoreIfExtended, effectiveAlphabetSize, charGuessability, normalizedCommonMatch, isCaseInversion, checkEditDistanceFromPrevious, failureReasonHistogram, policyImpact, weakestPasswords, primaryWeaknessMessage, assessRisk, telemetryRecord, hashPassword, hashPasswordWithParams, verifyPasswordHash, hashPasswordNormalized, verifyPasswordNormalized, verifyPasswordFixedTime, isHashStale, hashPasswordWithPepper, verifyWithPepperRotation, batchHashPasswords, batchFingerprintSha256, findReuseClusters, batchRehash, verifyAndMigrateAlgo, memoryPerHashForBudget, argon2WorkUnits, reuseFingerprint, breachLookupKey, hashPasswordSha1 } = nativeBinding
 
+module.exports.Severity = Severity
+module.exports.EditKind = EditKind
+module.exports.ScoringMode = ScoringMode
 module.exports.checkPasswordPolicy = checkPasswordPolicy
+module.exports.checkPasswordPolicyStable = checkPasswordPolicyStable
+module.exports.checkPasswordPolicyWithContext = checkPasswordPolicyWithContext
+module.exports.checkPasswordPolicyLocalized = checkPasswordPolicyLocalized
+module.exports.analyzePassphrase = analyzePassphrase
+module.exports.analyzeAndHashParallelAsync = analyzeAndHashParallelAsync
+module.exports.loadForbiddenSubstrings = loadForbiddenSubstrings
+module.exports.analyzeBatch = analyzeBatch
+module.exports.batchPolicyColumns = batchPolicyColumns
+module.exports.compliancePlan = compliancePlan
+module.exports.minimumRelaxation = minimumRelaxation
+module.exports.generateFromSeed = generateFromSeed
+module.exports.scoringModel = scoringModel
+module.exports.embeddedResources = embeddedResources
+module.exports.scoreWithModelJson = scoreWithModelJson
+module.exports.scoreIfExtended = scoreIfExtended
+module.exports.effectiveAlphabetSize = effectiveAlphabetSize
+module.exports.charGuessability = charGuessability
+module.exports.normalizedCommonMatch = normalizedCommonMatch
+module.exports.isCaseInversion = isCaseInversion
+module.exports.checkEditDistanceFromPrevious = checkEditDistanceFromPrevious
+module.exports.failureReasonHistogram = failureReasonHistogram
+module.exports.policyImpact = policyImpact
+module.exports.weakestPasswords = weakestPasswords
+module.exports.primaryWeaknessMessage = primaryWeaknessMessage
+module.exports.assessRisk = assessRisk
+module.exports.telemetryRecord = telemetryRecord
 module.exports.hashPassword = hashPassword
+module.exports.hashPasswordWithParams = hashPasswordWithParams
 module.exports.verifyPasswordHash = verifyPasswordHash
+module.exports.hashPasswordNormalized = hashPasswordNormalized
+module.exports.verifyPasswordNormalized = verifyPasswordNormalized
+module.exports.verifyPasswordFixedTime = verifyPasswordFixedTime
+module.exports.isHashStale = isHashStale
+module.exports.hashPasswordWithPepper = hashPasswordWithPepper
+module.exports.verifyWithPepperRotation = verifyWithPepperRotation
 module.exports.batchHashPasswords = batchHashPasswords
+module.exports.batchFingerprintSha256 = batchFingerprintSha256
+module.exports.findReuseClusters = findReuseClusters
+module.exports.batchRehash = batchRehash
+module.exports.verifyAndMigrateAlgo = verifyAndMigrateAlgo
+module.exports.memoryPerHashForBudget = memoryPerHashForBudget
+module.exports.argon2WorkUnits = argon2WorkUnits
+module.exports.reuseFingerprint = reuseFingerprint
+module.exports.breachLookupKey = breachLookupKey
 module.exports.hashPasswordSha1 = hashPasswordSha1
@@ -1,14 +1,26 @@
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
-use argon2::password_hash::{rand_core::OsRng, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::password_hash::{rand_core::{OsRng, RngCore}, SaltString};
 use sha1::{Digest, Sha1}; // for HIBP
-use std::collections::HashMap;
+use sha2::Sha256;
+use hmac::{Hmac, Mac};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use serde::{Deserialize, Serialize};
 use rayon::prelude::*; // parallel iterator
 use regex::Regex;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use once_cell::sync::Lazy; // for regex precompiling
+use aho_corasick::AhoCorasick;
+use std::sync::RwLock;
+use zeroize::Zeroize;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::SeedableRng;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use zxcvbn::matching::patterns::MatchPattern;
 
 // Structs for API Response
 #[napi(object)]
@@ -17,8 +29,23 @@ pub struct PasswordAnalysis {
     pub is_compliant: bool,
     pub strength_score: u32,
     pub entropy_bits: f64,
+    /// `entropy_bits` discounted for detected low-entropy constructions
+    pub effective_entropy_bits: f64,
+    /// Alternate estimate from the observed character distribution: length times log2 of
+    /// `effective_alphabet_size`, so skewed passwords like "aaaab" rate low whatever their classes
+    pub distribution_entropy_bits: f64,
     pub pattern_analysis: PatternAnalysis,
+    /// Common padding token (e.g. "!!!", "123") found at the start or end
+    pub padding_token: Option<String>,
+    /// Strength score of the password with `padding_token` removed
+    pub base_strength_score: Option<u32>,
+    /// Messages of `feedback_items`, in the same order
     pub feedback: Vec<String>,
+    /// Critical items first, then warnings, then info
+    pub feedback_items: Vec<FeedbackItem>,
+    /// SHA-256 hex of every other field except `analysis_time_ms`, so identical results hash
+    /// identically and a change in behavior for the same input changes the hash
+    pub result_hash: String,
     pub analysis_time_ms: i64,
 }
 
@@ -29,190 +56,4138 @@ pub struct PatternAnalysis {
     pub has_lowercase: bool,
     pub has_numbers: bool,
     pub has_symbols: bool,
+    /// Any character outside ASCII, like an accented letter or an emoji
+    pub has_non_ascii: bool,
     pub length: u32,
+    /// `length` holds the `bucket_length` bucket index rather than the length
+    pub length_bucketed: bool,
     pub repeated_chars: u32,
     pub sequential_chars: u32,
+    pub longest_class_run: u32,
+    /// Password is an identity term reversed or rotated (e.g. "htimsj" for "jsmith")
+    pub identity_transformed: bool,
+    /// Contains the user's `date_of_birth` as MMDD, DDMM, YYYY, an 8-digit or a 2-digit-year form;
+    /// only checked when a user context with a birth date is supplied
+    pub contains_dob: bool,
+    /// Zero-width characters, a BOM or trailing control characters were stripped before analysis
+    pub had_paste_artifacts: bool,
+    /// A short unit of alternating character classes repeated, like "a1a1a1a1" or "Z!Z!Z!"
+    pub alternating_pattern: bool,
+    /// Letters and digits interleaved with both advancing in step, like "a1b2c3" or "3c2b1a"
+    pub interleaved_sequence: bool,
+    /// The only uppercase letter is the leading one, as in "Password1!"
+    pub only_first_capitalized: bool,
+    /// Letters strictly alternate case, as in "pAsSwOrD"
+    pub studly_caps: bool,
+    /// A straight-line keyboard walk typed partly with shift held, like "!QAZ2wsx" or "@WSX#EDC"
+    pub shift_keyboard_walk: bool,
+    /// Built around a popular given name, allowing for case, leet and digit/symbol affixes ("j3ssica1")
+    pub contains_common_name: bool,
+    /// A well-known phrase typed without its spaces, like "correcthorsebatterystaple"
+    pub concatenated_phrase: bool,
+    /// The common password that leet substitutions resolve to ("password" for "p@ssw0rd")
+    pub leet_common_base: Option<String>,
+    /// First of the policy's `banned_sequences` found, as written in the policy
+    pub contains_banned_sequence: Option<String>,
+    /// Contains a term from the list loaded with `load_forbidden_substrings`
+    pub contains_forbidden: bool,
+    /// The forbidden term that matched, as it was loaded
+    pub forbidden_match: Option<String>,
+}
+
+#[napi(string_enum)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)] // napi already derives Clone + Copy
+// Declared most severe first, so sorting ascending puts critical items on top
+pub enum Severity {
+    /// Fails compliance
+    Critical,
+    Warning,
+    Info,
+}
+
+// `code` is stable across releases, `message` is the human wording and may change
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FeedbackItem {
+    pub code: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl FeedbackItem {
+    fn new(code: &str, severity: Severity, message: impl Into<String>) -> Self {
+        FeedbackItem { code: code.to_string(), severity, message: message.into() }
+    }
+}
+
+// Every field is optional so callers only spell out what they override
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct PasswordPolicy {
+    pub min_length: Option<u32>,
+    pub min_strength_score: Option<u32>,
+    /// Longest allowed run of one character class (e.g. 5 digits in a row), unlimited when unset
+    pub max_class_run: Option<u32>,
+    /// Requires a symbol that is neither the first nor the last character
+    pub require_interior_symbol: Option<bool>,
+    /// Lets long digit-only passwords (PIN-like flows) pass on length and patterns instead of score
+    pub allow_numeric_only: Option<bool>,
+    /// Rejects passwords containing the current calendar year (e.g. "Summer2026!")
+    pub forbid_current_year: Option<bool>,
+    /// Leading characters (e.g. a mandated department code) left out of sequence/repeat/keyboard checks
+    pub ignore_first_chars: Option<u32>,
+    /// Trailing characters left out of sequence/repeat/keyboard checks
+    pub ignore_last_chars: Option<u32>,
+    /// Minimum estimated time (seconds) to crack offline against a fast hash at 10^10 guesses/s
+    pub min_offline_crack_seconds: Option<f64>,
+    /// Rejects passwords shaped like an email address (often the user's own login)
+    pub forbid_email_format: Option<bool>,
+    /// Overrides the built-in scoring model's mode, e.g. `Entropy` for scores that never drop as entropy rises
+    pub scoring_mode: Option<ScoringMode>,
+    /// Org-specific sequences the password may not contain, case-insensitive. `#` matches any
+    /// digit and `?` any single character, e.g. "SKU-####"
+    pub banned_sequences: Option<Vec<String>>,
+    /// Report `PatternAnalysis.length` as a bucket index instead of the exact length (0 for 1-7,
+    /// 1 for 8-11, 2 for 12-15, 3 for 16+) and set `length_bucketed`. Checks and scoring still use
+    /// the true length, and functions that take an analysis back honor the flag
+    pub bucket_length: Option<bool>,
+    /// Fewest character edits (insertions, deletions, substitutions) a new password must be from
+    /// each previous one, enforced by `check_edit_distance_from_previous` at change time
+    pub min_edit_distance_from_previous: Option<u32>,
+    /// Suggests adding a non-ASCII character (accented letter, emoji, ...) to pure-ASCII passwords
+    pub encourage_non_ascii: Option<bool>,
+    /// Rejects any non-ASCII character, for systems that can't store Unicode
+    pub ascii_only: Option<bool>,
+}
+
+impl PasswordPolicy {
+    fn min_length(&self) -> u32 {
+        self.min_length.unwrap_or(DEFAULT_MIN_LENGTH)
+    }
+
+    fn min_strength_score(&self) -> u32 {
+        self.min_strength_score.unwrap_or(DEFAULT_MIN_STRENGTH_SCORE)
+    }
+
+    fn exempts_numeric(&self, password: &str) -> bool {
+        self.allow_numeric_only.unwrap_or(false) && is_numeric_only(password)
+    }
+
+    fn scoring_model(&self) -> Cow<'static, ScoringModel> {
+        match self.scoring_mode {
+            Some(mode) if mode != DEFAULT_SCORING_MODEL.mode => Cow::Owned(ScoringModel { mode, ..DEFAULT_SCORING_MODEL.clone() }),
+            _ => Cow::Borrowed(&*DEFAULT_SCORING_MODEL),
+        }
+    }
+}
+
+#[napi(string_enum)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)] // napi already derives Clone + Copy
+pub enum EditKind {
+    AppendChars,
+    AddUppercase,
+    AddLowercase,
+    AddNumber,
+    AddSymbol,
+    BreakSequence,
+    InsertSymbol,
+}
+
+/// One step of a compliance plan. Additions are appended to the end of the password,
+/// `BreakSequence` and `InsertSymbol` insert a symbol before the character at `position`
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EditStep {
+    pub kind: EditKind,
+    /// Number of characters to append, only set for `AppendChars`
+    pub count: Option<u32>,
+    /// Character index (in the password as edited so far), only set for insertions
+    pub position: Option<u32>,
+}
+
+// What the signup form knows about the user, every field optional
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct UserContext {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub full_name: Option<String>,
+    /// YYYY-MM-DD
+    pub date_of_birth: Option<String>,
+    /// Names of the service or company the password is for
+    pub service_terms: Option<Vec<String>>,
+}
+
+// (year, month, day)
+type BirthDate = (u32, u32, u32);
+
+impl UserContext {
+    // Username, email and its local part, and each name part plus the name run together
+    fn identity_terms(&self) -> Vec<String> {
+        let mut terms: Vec<String> = self.username.iter().cloned().collect();
+        if let Some(email) = &self.email {
+            terms.push(email.clone());
+            if let Some((local_part, _)) = email.split_once('@') {
+                terms.push(local_part.to_string());
+            }
+        }
+        if let Some(full_name) = &self.full_name {
+            terms.extend(full_name.split_whitespace().map(str::to_string));
+            terms.push(full_name.split_whitespace().collect());
+        }
+        terms
+    }
+
+    fn birth_date(&self) -> Result<Option<BirthDate>> {
+        let Some(date) = self.date_of_birth.as_deref().filter(|date| !date.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let invalid = || Error::from_reason("date_of_birth must be YYYY-MM-DD");
+        let parts: Vec<&str> = date.trim().split('-').collect();
+        let [year, month, day] = parts.as_slice() else { return Err(invalid()) };
+        let (year, month, day): (u32, u32, u32) = (
+            year.parse().map_err(|_| invalid())?,
+            month.parse().map_err(|_| invalid())?,
+            day.parse().map_err(|_| invalid())?,
+        );
+        if year.to_string().len() != 4 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Err(invalid());
+        }
+        Ok(Some((year, month, day)))
+    }
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PolicyColumns {
+    pub scores: Vec<u32>,
+    pub compliant: Vec<bool>,
+    /// `entropy_bits` per password
+    pub entropy: Vec<f64>,
+    /// `PatternAnalysis.length` per password, the bucket index when the policy sets `bucket_length`
+    pub length: Vec<u32>,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PolicyRelaxation {
+    pub already_compliant: bool,
+    pub relaxations: Vec<ConstraintRelaxation>,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConstraintRelaxation {
+    /// Policy field as passed from JS, e.g. "minLength"
+    pub constraint: String,
+    /// Setting the password would pass with; 0/1 for switches turned off/on
+    pub relaxed_value: f64,
+    pub message: String,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VerifyPepperResult {
+    pub valid: bool,
+    /// Index into the supplied peppers of the one that verified
+    pub pepper_index: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MigrateResult {
+    pub valid: bool,
+    /// Hash in the target algorithm, only set when valid and the stored hash used another one
+    pub new_hash: Option<String>,
+}
+
+// HIBP result for the password, `most_recent_year` is the latest breach it appeared in
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BreachInfo {
+    pub count: u32,
+    pub most_recent_year: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RiskConfig {
+    /// How far a breach pushes risk, 0 to 1 (default 0.5). 0 ignores breaches, 0.5 scales the push
+    /// by sighting count and recency, 1 makes any breach maximum risk; values between interpolate
+    pub breach_weight: Option<f64>,
+}
+
+// Field set is the event schema, only ever add fields
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TelemetryRecord {
+    pub compliant: bool,
+    /// `strength_score` rounded down to a multiple of 20
+    pub score_band: u32,
+    /// `effective_entropy_bits` rounded down to a multiple of 20, capped at 100
+    pub entropy_bucket: u32,
+    /// "<8", "8-11", "12-15" or "16+"
+    pub length_bucket: String,
+    /// Character classes present, 0 to 4
+    pub class_count: u32,
+    /// Common password, phrase or name, including leet forms
+    pub common_word: bool,
+    pub too_short: bool,
+    /// Character sequences or keyboard walks
+    pub sequential: bool,
+    /// Repeated characters or a repeated alternating unit
+    pub repeated: bool,
+    /// Built from the user's own details
+    pub personal_info: bool,
+    /// First-letter-only or alternating capitalization
+    pub predictable_case: bool,
+    /// Matched the loaded forbidden-substring list
+    pub blocklisted: bool,
+    /// Below the policy's strength score or crack-time minimum
+    pub low_strength: bool,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RiskAssessment {
+    /// 0 (low risk) to 100 (high risk)
+    pub risk_score: u32,
+    /// 1.0 for a breach this year, halving every 4 years after, 0 when not breached
+    pub breach_recency_weight: f64,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WeakEntry {
+    /// Position in the input batch
+    pub index: u32,
+    pub strength_score: u32,
+    /// Code of the primary weakness (see `primary_weakness_message`), none for strong passwords
+    pub top_reason: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PolicyImpact {
+    /// Compliant under the old policy, not under the new one
+    pub newly_failing: u32,
+    /// Non-compliant under the old policy, compliant under the new one
+    pub newly_passing: u32,
+    pub unchanged: u32,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PassphraseAnalysis {
+    pub word_count: u32,
+    /// Words counted once however often they repeat, compared case-insensitively
+    pub distinct_word_count: u32,
+    /// Lowercased words that appear more than once, in order of first appearance
+    pub repeated_words: Vec<String>,
+    /// From distinct words only, each assumed drawn from a diceware-sized (7,776 word) list
+    pub entropy_bits: f64,
+    /// At least `min_words` distinct words
+    pub is_compliant: bool,
+    pub feedback_items: Vec<FeedbackItem>,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FingerprintBatch {
+    /// SHA-256 hex per input, in input order
+    pub fingerprints: Vec<String>,
+    pub distinct_count: u32,
+    /// Occurrences of the most reused fingerprint, 0 for an empty batch
+    pub max_reuse_count: u32,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReuseCluster {
+    /// SHA-256 hex shared by every member
+    pub fingerprint: String,
+    /// Input positions, ascending
+    pub member_indices: Vec<u32>,
+    pub count: u32,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ResourceInfo {
+    pub name: String,
+    pub entry_count: u32,
+    /// Crate version the list shipped with
+    pub version: String,
+    /// SHA-256 hex of the entries joined with newlines, in their embedded order
+    pub sha256: String,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize)]
+pub struct AnalyzeAndHashResult {
+    pub analysis: PasswordAnalysis,
+    /// Argon2 PHC string, none when the password is non-compliant
+    pub hash: Option<String>,
+}
+
+pub struct AnalyzeAndHash {
+    password: String,
+    policy: PasswordPolicy,
+}
+
+impl Task for AnalyzeAndHash {
+    type Output = AnalyzeAndHashResult;
+    type JsValue = AnalyzeAndHashResult;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let password = self.password.as_str();
+        let (analysis, hash) = rayon::join(
+            || {
+                let start_time = Instant::now();
+                let mut analysis = analyze_password(password, &self.policy, &[]);
+                analysis.analysis_time_ms = start_time.elapsed().as_millis() as i64;
+                analysis
+            },
+            || {
+                let salt = SaltString::generate(&mut OsRng);
+                Argon2::default()
+                    .hash_password(password.as_bytes(), &salt)
+                    .map(|hash| hash.to_string())
+                    .map_err(|_| Error::from_reason("Failed to hash password"))
+            },
+        );
+        self.password.zeroize();
+
+        let mut hash = hash?;
+        if !analysis.is_compliant {
+            hash.zeroize();
+            return Ok(AnalyzeAndHashResult { analysis, hash: None });
+        }
+        Ok(AnalyzeAndHashResult { analysis, hash: Some(hash) })
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+pub struct VerifyFixedTime {
+    password: String,
+    hash: String,
+    min_duration: Duration,
+}
+
+impl Task for VerifyFixedTime {
+    type Output = bool;
+    type JsValue = bool;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let start_time = Instant::now();
+        let valid = verify_password_hash(std::mem::take(&mut self.password), std::mem::take(&mut self.hash))?;
+
+        // Runs on a libuv worker, so the wait never holds up the event loop
+        if let Some(remaining) = self.min_duration.checked_sub(start_time.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+        Ok(valid)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+// The constants behind strength_score, exported so a deployment can pin them in config and
+// notice when an upgrade changes scoring. Every field is required, a pinned JSON is never
+// topped up with the current defaults
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoringModel {
+    pub version: u32,
+    pub mode: ScoringMode,
+    /// Effective entropy that earns `max_score` in `Entropy` mode
+    pub entropy_target_bits: f64,
+    /// Points for the highest tier whose `min_length` the password reaches
+    pub length_tiers: Vec<LengthTier>,
+    pub lowercase_bonus: u32,
+    pub uppercase_bonus: u32,
+    /// Replaces `uppercase_bonus` when the only capital is the first letter
+    pub predictable_uppercase_bonus: u32,
+    /// Replaces `lowercase_bonus` + `uppercase_bonus` when letters strictly alternate case
+    pub studly_case_bonus: u32,
+    pub number_bonus: u32,
+    pub symbol_bonus: u32,
+    pub repeat_penalty: u32,
+    pub sequence_penalty: u32,
+    pub shift_walk_penalty: u32,
+    pub max_score: u32,
+}
+
+#[napi(string_enum)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)] // napi already derives Clone + Copy
+pub enum ScoringMode {
+    /// Length tier plus a bonus per character class, minus pattern penalties
+    ClassBonus,
+    /// `effective_entropy_bits / entropy_target_bits * max_score`, clamped to `max_score`.
+    /// More entropy never scores lower; class bonuses, tiers and penalties are ignored
+    Entropy,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthTier {
+    pub min_length: u32,
+    pub points: u32,
+}
+
+impl Default for ScoringModel {
+    // Length scoring (I made up the numbers)
+    fn default() -> Self {
+        ScoringModel {
+            version: SCORING_MODEL_VERSION,
+            mode: ScoringMode::ClassBonus,
+            entropy_target_bits: 80.0,
+            length_tiers: vec![
+                LengthTier { min_length: 0, points: 5 },
+                LengthTier { min_length: 8, points: 25 },
+                LengthTier { min_length: 12, points: 40 },
+            ],
+            lowercase_bonus: 10,
+            uppercase_bonus: 10,
+            predictable_uppercase_bonus: 3,
+            studly_case_bonus: 8,
+            number_bonus: 15,
+            symbol_bonus: 20,
+            repeat_penalty: 10,
+            sequence_penalty: 15,
+            shift_walk_penalty: 30,
+            max_score: 100,
+        }
+    }
+}
+
+// A plaintext the caller has already verified against its old hash
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VerifiedPassword {
+    pub password: String,
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Argon2Params {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        Argon2Params {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+#[napi(object)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct BcryptParams {
+    pub cost: u32,
+}
+
+impl Default for BcryptParams {
+    fn default() -> Self {
+        BcryptParams { cost: bcrypt::DEFAULT_COST }
+    }
+}
+
+// Loaded terms kept alongside the automaton so a match can report the original spelling
+struct ForbiddenSubstrings {
+    automaton: AhoCorasick,
+    terms: Vec<String>,
+}
+
+// Algorithm + params resolved once, then reused for every hash in a batch
+enum HashScheme {
+    Argon2(Params),
+    Bcrypt(u32),
 }
 
 const COMMON_PASSWORDS: &[&str] = &["password", "123456", "qwerty", "admin"];
 
+// Popular given names, lowercase
+const COMMON_NAMES: &[&str] = &[
+    "michael", "jennifer", "jessica", "ashley", "matthew", "joshua", "christopher", "daniel",
+    "david", "andrew", "james", "robert", "john", "joseph", "william", "thomas", "charles",
+    "anthony", "nicholas", "justin", "brandon", "tyler", "ryan", "kevin", "jason", "eric",
+    "steven", "brian", "amanda", "sarah", "stephanie", "elizabeth", "melissa", "nicole",
+    "heather", "michelle", "amber", "emily", "samantha", "rachel", "lauren", "megan",
+    "hannah", "emma", "olivia", "sophia", "isabella", "madison", "charlie", "maria",
+    "jordan", "taylor", "alexander", "benjamin", "jacob", "ethan", "jack", "george",
+];
+
+// Famous passphrases and stock phrases, lowercase with spaces
+const COMMON_PHRASES: &[&str] = &[
+    "correct horse battery staple", "let me in please", "let me in", "i love you", "trust no one",
+    "open sesame", "the quick brown fox", "the quick brown fox jumps over the lazy dog",
+    "may the force be with you", "to be or not to be", "hello world", "change me",
+    "password is password", "welcome to the jungle", "all your base are belong to us",
+];
+
+const SCORING_MODEL_VERSION: u32 = 4;
+
+// Guess rate for the offline fast-hash scenario (zxcvbn's offline_fast_hashing_1e10_per_second)
+const OFFLINE_FAST_HASH_GUESSES_PER_SECOND: f64 = 1e10;
+// log2(95), a uniformly random printable ASCII character
+const RANDOM_CHAR_BITS: f64 = 6.569855608330948;
+
+// English is what generate_feedback writes, other languages are translated from the codes
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FeedbackLanguage {
+    English,
+    Spanish,
+}
+
+impl FeedbackLanguage {
+    fn from_locale(locale: &str) -> Result<Self> {
+        let language = locale.trim().split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "en" => Ok(FeedbackLanguage::English),
+            "es" => Ok(FeedbackLanguage::Spanish),
+            _ => Err(Error::from_reason(format!("Unsupported locale: {}", locale))),
+        }
+    }
+}
+
+// Unicode form applied before hashing, recorded as a "nfc$"/"nfkc$" marker ahead of the PHC string
+#[derive(Clone, Copy)]
+enum NormalizationForm {
+    Nfc,
+    Nfkc,
+}
+
+impl NormalizationForm {
+    fn from_name(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "nfc" => Ok(NormalizationForm::Nfc),
+            "nfkc" => Ok(NormalizationForm::Nfkc),
+            _ => Err(Error::from_reason(format!("Unsupported normalization form: {}", name))),
+        }
+    }
+
+    fn marker(self) -> &'static str {
+        match self {
+            NormalizationForm::Nfc => "nfc",
+            NormalizationForm::Nfkc => "nfkc",
+        }
+    }
+
+    fn apply(self, password: &str) -> String {
+        match self {
+            NormalizationForm::Nfc => password.nfc().collect(),
+            NormalizationForm::Nfkc => password.nfkc().collect(),
+        }
+    }
+}
+
+// Tokens users tack onto a weak base word
+const PADDING_TOKENS: &[&str] = &[
+    "!@#$", "!@#", "!!!", "!!", "!", "?", ".", "12345", "1234", "123", "321", "1!", "!1", "007", "69", "01", "1",
+];
+
+// Padding is only split off when this much is left, otherwise the token is the password
+const MIN_PADDED_BASE_LEN: usize = 4;
+
+const DEFAULT_MIN_LENGTH: u32 = 8;
+const DEFAULT_MIN_STRENGTH_SCORE: u32 = 51;
+const DEFAULT_MIN_PASSPHRASE_WORDS: u32 = 4;
+// log2(7776), one word from a diceware list
+const PASSPHRASE_WORD_BITS: f64 = 12.92481250360578;
+
+// Below this the password is flagged as low-entropy in weakness summaries
+const LOW_ENTROPY_BITS: f64 = 40.0;
+
+// Breach weight that keeps the count/recency scaling as-is
+const DEFAULT_BREACH_WEIGHT: f64 = 0.5;
+
+// A breach's weight in the risk score halves every this many years
+const BREACH_HALF_LIFE_YEARS: f64 = 4.0;
+
+// Every rehash thread holds a full Argon2 memory block, so don't fan out to every core
+const MAX_REHASH_THREADS: usize = 4;
+
+// Upper bound on planning iterations, a fixable password never needs anywhere near this many
+const MAX_PLAN_STEPS: usize = 64;
+
+// SaltString holds at most 64 B64 characters
+const MAX_SALT_LEN: usize = 48;
+
+// generate_from_seed alphabet, look-alikes (l/1/I, o/O/0) left out
+const GENERATOR_CLASSES: [&str; 4] = [
+    "abcdefghijkmnpqrstuvwxyz",
+    "ABCDEFGHJKLMNPQRSTUVWXYZ",
+    "23456789",
+    "!#$%&*+-=?@^_",
+];
+const GENERATED_MIN_LENGTH: usize = 16;
+const MIN_SEED_LEN: usize = 16;
+const MAX_GENERATE_ATTEMPTS: usize = 32;
+
+static FORBIDDEN_SUBSTRINGS: Lazy<RwLock<Option<ForbiddenSubstrings>>> = Lazy::new(|| RwLock::new(None));
+
+static DEFAULT_SCORING_MODEL: Lazy<ScoringModel> = Lazy::new(ScoringModel::default);
+
+// US QWERTY rows as (unshifted, shifted), columns line up between the two
+const KEYBOARD_ROWS: [(&str, &str); 4] = [
+    ("1234567890-=", "!@#$%^&*()_+"),
+    ("qwertyuiop[]", "QWERTYUIOP{}"),
+    ("asdfghjkl;'", "ASDFGHJKL:\""),
+    ("zxcvbnm,./", "ZXCVBNM<>?"),
+];
+const MIN_KEYBOARD_WALK_LEN: usize = 4;
+const MIN_INTERLEAVED_LEN: usize = 6;
+
 // Regex patterns are compiled once at startup
+// The bare 4-digit run, which every allowed numeric-only password would trip
+const DIGIT_RUN_PATTERN: &str = r"\d{4}";
+
 static COMMON_PATTERNS_RE: Lazy<Vec<Regex>> = Lazy::new(|| {
     vec![
-        Regex::new(r"\d{4}").unwrap(), // 4 consecutive digits
+        Regex::new(DIGIT_RUN_PATTERN).unwrap(), // 4 consecutive digits
         Regex::new(r"(abc|bcd|cde|def|efg|fgh|ghi|hij|ijk|jkl)").unwrap(),
         Regex::new(r"(123|234|345|456|567|678|789)").unwrap(),
         Regex::new(r"(qwe|wer|ert|rty|tyu|yui|uio|iop)").unwrap(),
     ]
 });
 
+// Deliberately loose, anything like "name@host.tld" with no spaces
+static EMAIL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[^\s@]+@[^\s@]+\.[A-Za-z]{2,}$").unwrap());
+
 #[napi]
-// Analyzes a password against policies without hashing it
-pub fn check_password_policy(password: String) -> Result<PasswordAnalysis> {
+// Analyzes a password against policies without hashing it, the default policy is used when none is given.
+// identity_terms are the user's own details (username, email, ...) that the password shouldn't be built from
+pub fn check_password_policy(password: String, policy: Option<PasswordPolicy>, identity_terms: Option<Vec<String>>) -> Result<PasswordAnalysis> {
     let start_time = Instant::now();
     
-    let pattern_analysis = analyze_patterns(&password);
-    let strength_score = calculate_strength_score(&password, &pattern_analysis);
-    let entropy_bits = calculate_entropy(&password, &pattern_analysis);
+    let identity_terms = identity_terms.unwrap_or_default();
+    let mut analysis = analyze_password(&password, &policy.unwrap_or_default(), &identity_terms);
+    analysis.analysis_time_ms = start_time.elapsed().as_millis() as i64;
     
-    let feedback = generate_feedback(&password, &pattern_analysis, strength_score);
-    
-    let is_compliant = password.len() >= 8 
-        && strength_score > 50 
-        && !is_common_password(&password)
-        && pattern_analysis.sequential_chars == 0;
-    
-    let analysis_time_ms = start_time.elapsed().as_millis() as i64;
-    
-    Ok(PasswordAnalysis {
-        is_compliant,
-        strength_score,
-        entropy_bits,
-        pattern_analysis,
-        feedback, 
-        analysis_time_ms,
-    })
+    Ok(analysis)
 }
 
+#[napi]
+/// Same as `check_password_policy` but `analysis_time_ms` is always 0, so identical inputs
+/// give identical results (for equality assertions in tests)
+pub fn check_password_policy_stable(password: String, policy: Option<PasswordPolicy>, identity_terms: Option<Vec<String>>) -> Result<PasswordAnalysis> {
+    let identity_terms = identity_terms.unwrap_or_default();
+    Ok(analyze_password(&password, &policy.unwrap_or_default(), &identity_terms))
+}
 
 #[napi]
-/// Hashes a password securely using Argon2
-pub fn hash_password(password: String) -> Result<String> {
-    let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
-    
-    match argon2.hash_password(password.as_bytes(), &salt) {
-        Ok(hash) => Ok(hash.to_string()),
-        Err(_) => Err(Error::from_reason("Failed to hash password")),
+/// Single signup entrypoint: runs every context-dependent check against `context` on top of the
+/// policy. Username, email (whole and local part), name parts and service terms may not appear in
+/// the password, nor may the date of birth written as a date; the identity terms also feed the
+/// reversal/rotation check. Fails when `date_of_birth` isn't YYYY-MM-DD
+pub fn check_password_policy_with_context(password: String, policy: Option<PasswordPolicy>, context: UserContext) -> Result<PasswordAnalysis> {
+    let start_time = Instant::now();
+
+    let birth_date = context.birth_date()?;
+    let identity_terms = context.identity_terms();
+    let mut analysis = analyze_password_in_context(&password, &policy.unwrap_or_default(), &identity_terms, Some((&context, birth_date)));
+    analysis.analysis_time_ms = start_time.elapsed().as_millis() as i64;
+
+    Ok(analysis)
+}
+
+#[napi]
+/// `check_password_policy` with feedback messages in `locale` ("en" or "es", region suffixes like
+/// "es-MX" accepted). Codes and severities are language-independent, so the UI gets the stable
+/// code and the translated text in one payload. Fails on an unsupported locale
+pub fn check_password_policy_localized(password: String, policy: Option<PasswordPolicy>, locale: String) -> Result<PasswordAnalysis> {
+    let start_time = Instant::now();
+
+    let language = FeedbackLanguage::from_locale(&locale)?;
+    let policy = policy.unwrap_or_default();
+    let mut analysis = analyze_password(&password, &policy, &[]);
+    if language == FeedbackLanguage::Spanish {
+        // The analysis saw the password without paste artifacts, so the crack estimate must too
+        let cleaned = strip_paste_artifacts(&password);
+        for item in analysis.feedback_items.iter_mut() {
+            if let Some(message) = spanish_message(&item.code, &cleaned, &analysis.pattern_analysis, analysis.padding_token.as_deref(), &policy) {
+                item.message = message;
+            }
+        }
+        analysis.feedback = analysis.feedback_items.iter().map(|item| item.message.clone()).collect();
+        analysis.result_hash = analysis_result_hash(&analysis);
     }
+    analysis.analysis_time_ms = start_time.elapsed().as_millis() as i64;
+
+    Ok(analysis)
 }
 
 #[napi]
-// Verifies a plaintext password against a stored Argon2 hash
-pub fn verify_password_hash(password: String, hash: String) -> Result<bool> {
-    match PasswordHash::new(&hash) {
-        Ok(parsed_hash) => {
-            let argon2 = Argon2::default();
-            Ok(argon2.verify_password(password.as_bytes(), &parsed_hash).is_ok())
+/// Passphrase check: words are split on whitespace, '-', '_' and '.', and repeats count once,
+/// so "apple apple apple orange" has 2 distinct words and the entropy of 2. Non-compliant when
+/// there are fewer than `min_words` (default 4) distinct words
+pub fn analyze_passphrase(passphrase: String, min_words: Option<u32>) -> PassphraseAnalysis {
+    let min_words = min_words.unwrap_or(DEFAULT_MIN_PASSPHRASE_WORDS);
+    let words: Vec<String> = passphrase
+        .split(|c: char| c.is_whitespace() || matches!(c, '-' | '_' | '.'))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut distinct: Vec<&String> = Vec::new();
+    let mut repeated_words: Vec<String> = Vec::new();
+    for word in &words {
+        if !distinct.contains(&word) {
+            distinct.push(word);
+        } else if !repeated_words.contains(word) {
+            repeated_words.push(word.clone());
         }
-        Err(_) => Ok(false), // If hash fails, it can't be valid
     }
+    let distinct_word_count = distinct.len() as u32;
+
+    let mut feedback_items = Vec::new();
+    if distinct_word_count < min_words {
+        feedback_items.push(FeedbackItem::new("TOO_FEW_WORDS", Severity::Critical,
+            format!("Passphrase needs at least {} different words.", min_words)));
+    }
+    if !repeated_words.is_empty() {
+        feedback_items.push(FeedbackItem::new("REPEATED_WORDS", Severity::Warning,
+            format!("Repeated words add no strength: {}.", repeated_words.join(", "))));
+    }
+
+    PassphraseAnalysis {
+        word_count: words.len() as u32,
+        distinct_word_count,
+        entropy_bits: distinct_word_count as f64 * PASSPHRASE_WORD_BITS,
+        is_compliant: distinct_word_count >= min_words,
+        repeated_words,
+        feedback_items,
+    }
+}
+
+#[napi(ts_return_type = "Promise<AnalyzeAndHashResult>")]
+/// Registration path: analyzes and Argon2-hashes the password off the JS thread, running both
+/// at once on the rayon pool. The hash is computed speculatively and discarded (zeroized) when
+/// the password turns out non-compliant, so `hash` is only set for compliant passwords
+pub fn analyze_and_hash_parallel_async(password: String, policy: Option<PasswordPolicy>) -> AsyncTask<AnalyzeAndHash> {
+    AsyncTask::new(AnalyzeAndHash { password, policy: policy.unwrap_or_default() })
 }
 
 #[napi]
-// Hashes a large number of passwords in parallel
-pub fn batch_hash_passwords(passwords: Vec<String>) -> Result<HashMap<String, String>> {
-    let results: HashMap<String, String> = passwords
+/// Replaces the global forbidden-substring list used by every policy check. Terms are compiled
+/// into one Aho-Corasick automaton so large lists stay fast, and matching is case-insensitive.
+/// Loading an empty list turns the check off
+pub fn load_forbidden_substrings(terms: Vec<String>) -> Result<()> {
+    let terms: Vec<String> = terms.into_iter().filter(|term| !term.trim().is_empty()).collect();
+    let forbidden = if terms.is_empty() {
+        None
+    } else {
+        let lowered: Vec<String> = terms.iter().map(|term| term.to_lowercase()).collect();
+        let automaton = AhoCorasick::new(&lowered)
+            .map_err(|e| Error::from_reason(format!("Failed to build forbidden substring matcher: {}", e)))?;
+        Some(ForbiddenSubstrings { automaton, terms })
+    };
+
+    *FORBIDDEN_SUBSTRINGS.write().map_err(|_| Error::from_reason("Forbidden substring list is poisoned"))? = forbidden;
+    Ok(())
+}
+
+#[napi]
+// Analyzes a batch in parallel, results line up with the input. See par_map_chunked for chunk_size
+pub fn analyze_batch(passwords: Vec<String>, policy: Option<PasswordPolicy>, chunk_size: Option<u32>) -> Vec<PasswordAnalysis> {
+    let policy = policy.unwrap_or_default();
+    par_map_chunked(&passwords, chunk_size, |password| {
+        let start_time = Instant::now();
+        let mut analysis = analyze_password(password, &policy, &[]);
+        analysis.analysis_time_ms = start_time.elapsed().as_millis() as i64;
+        analysis
+    })
+}
+
+#[napi]
+/// Batch analysis as parallel columns (index i of every column is input i), much cheaper to
+/// marshal than full analyses and ready for charting
+pub fn batch_policy_columns(passwords: Vec<String>, policy: PasswordPolicy) -> PolicyColumns {
+    let rows: Vec<(u32, bool, f64, u32)> = passwords
         .par_iter()
         .map(|password| {
-            let hash = hash_password(password.clone()).unwrap_or_else(|_| "ERROR".to_string());
-            (password.clone(), hash)
+            let analysis = analyze_password(password, &policy, &[]);
+            (analysis.strength_score, analysis.is_compliant, analysis.entropy_bits, analysis.pattern_analysis.length)
         })
         .collect();
-    
-    Ok(results)
+
+    let mut columns = PolicyColumns {
+        scores: Vec::with_capacity(rows.len()),
+        compliant: Vec::with_capacity(rows.len()),
+        entropy: Vec::with_capacity(rows.len()),
+        length: Vec::with_capacity(rows.len()),
+    };
+    for (score, compliant, entropy, length) in rows {
+        columns.scores.push(score);
+        columns.compliant.push(compliant);
+        columns.entropy.push(entropy);
+        columns.length.push(length);
+    }
+    columns
 }
 
 #[napi]
-// hash password with sha1 for Have I Been Pwned
-pub fn hash_password_sha1(password: String) -> Result<String> {
-    let mut hasher = Sha1::new();
-    hasher.update(password.as_bytes());
-    let result = hasher.finalize();
-    Ok(hex::encode(result).to_uppercase())
-}
+/// Lists the ordered edits that, applied one after another, make the password compliant
+/// with `policy`. Returns an empty plan when the password already complies. Violations that
+/// appending/inserting can't fix (a forbidden term, the current year) are left in place
+pub fn compliance_plan(password: String, policy: PasswordPolicy) -> Vec<EditStep> {
+    let mut current = password;
+    let mut steps: Vec<EditStep> = Vec::new();
 
-fn analyze_patterns(password: &str) -> PatternAnalysis {
-    PatternAnalysis {
-        has_uppercase: password.chars().any(|c| c.is_uppercase()),
-        has_lowercase: password.chars().any(|c| c.is_lowercase()),
-        has_numbers: password.chars().any(|c| c.is_numeric()),
-        has_symbols: password.chars().any(|c| !c.is_alphanumeric()),
-        length: password.len() as u32,
-        repeated_chars: count_repeated_chars(password),
-        sequential_chars: count_sequential_chars(password),
+    // Bounded by iterations rather than steps, appends collapse into one step and some
+    // violations (forbidden terms, the current year) can't be fixed by editing at all
+    for _ in 0..MAX_PLAN_STEPS {
+        let analysis = analyze_password(&current, &policy, &[]);
+        if analysis.is_compliant {
+            break;
+        }
+
+        let step = next_edit_step(&current, &analysis, &policy);
+        apply_edit_step(&mut current, &step);
+
+        // Consecutive appends collapse into a single step
+        match steps.last_mut() {
+            Some(last) if last.kind == EditKind::AppendChars && step.kind == EditKind::AppendChars => {
+                last.count = Some(last.count.unwrap_or(0) + step.count.unwrap_or(0));
+            }
+            _ => steps.push(step),
+        }
     }
+
+    steps
 }
 
-fn calculate_strength_score(password: &str, analysis: &PatternAnalysis) -> u32 {
-    let mut score = 0u32;
-    
-    // Length scoring (I made up the numbers)
-    score += match password.len() {
-        0..=7 => 5,
-        8..=11 => 25,
-        _ => 40,
+#[napi]
+/// For "why can't I use this" messages: each single policy constraint that, relaxed on its own,
+/// would make the password compliant, with the loosest setting that lets it pass. Empty when
+/// no one constraint is the blocker (e.g. a common password fails regardless of policy)
+pub fn minimum_relaxation(password: String, policy: PasswordPolicy) -> PolicyRelaxation {
+    // The min-length candidate needs the exact length
+    let analysis = analyze_password(&password, &PasswordPolicy { bucket_length: None, ..policy.clone() }, &[]);
+    if analysis.is_compliant {
+        return PolicyRelaxation { already_compliant: true, relaxations: Vec::new() };
+    }
+
+    let patterns = &analysis.pattern_analysis;
+    let mut candidates: Vec<(&str, f64, String, PasswordPolicy)> = Vec::new();
+    if patterns.length < policy.min_length() {
+        candidates.push(("minLength", patterns.length as f64,
+            format!("Would pass if the minimum length were {}.", patterns.length),
+            PasswordPolicy { min_length: Some(patterns.length), ..policy.clone() }));
+    }
+    if analysis.strength_score < policy.min_strength_score() {
+        candidates.push(("minStrengthScore", analysis.strength_score as f64,
+            format!("Would pass if the minimum strength score were {}.", analysis.strength_score),
+            PasswordPolicy { min_strength_score: Some(analysis.strength_score), ..policy.clone() }));
+    }
+    if policy.max_class_run.is_some_and(|max_run| patterns.longest_class_run > max_run) {
+        candidates.push(("maxClassRun", patterns.longest_class_run as f64,
+            format!("Would pass if runs of {} same-kind characters were allowed.", patterns.longest_class_run),
+            PasswordPolicy { max_class_run: Some(patterns.longest_class_run), ..policy.clone() }));
+    }
+    if policy.require_interior_symbol.unwrap_or(false) {
+        candidates.push(("requireInteriorSymbol", 0.0,
+            "Would pass if symbols were allowed only at the start or end.".to_string(),
+            PasswordPolicy { require_interior_symbol: Some(false), ..policy.clone() }));
+    }
+    if !policy.allow_numeric_only.unwrap_or(false) && is_numeric_only(&password) {
+        candidates.push(("allowNumericOnly", 1.0,
+            "Would pass if numbers-only passwords were allowed.".to_string(),
+            PasswordPolicy { allow_numeric_only: Some(true), ..policy.clone() }));
+    }
+    if policy.forbid_current_year.unwrap_or(false) {
+        candidates.push(("forbidCurrentYear", 0.0,
+            "Would pass if the current year were allowed.".to_string(),
+            PasswordPolicy { forbid_current_year: Some(false), ..policy.clone() }));
+    }
+    if policy.forbid_email_format.unwrap_or(false) {
+        candidates.push(("forbidEmailFormat", 0.0,
+            "Would pass if email addresses were allowed.".to_string(),
+            PasswordPolicy { forbid_email_format: Some(false), ..policy.clone() }));
+    }
+    if policy.ascii_only.unwrap_or(false) {
+        candidates.push(("asciiOnly", 0.0,
+            "Would pass if non-ASCII characters were allowed.".to_string(),
+            PasswordPolicy { ascii_only: Some(false), ..policy.clone() }));
+    }
+    if let Some(sequence) = &patterns.contains_banned_sequence {
+        let remaining: Vec<String> = policy.banned_sequences.iter().flatten().filter(|banned| *banned != sequence).cloned().collect();
+        candidates.push(("bannedSequences", 0.0,
+            format!("Would pass if '{}' weren't a banned sequence.", sequence),
+            PasswordPolicy { banned_sequences: Some(remaining), ..policy.clone() }));
+    }
+    if let Some(min_seconds) = policy.min_offline_crack_seconds {
+        let crack_seconds = estimate_offline_crack_seconds(&password).floor();
+        if crack_seconds < min_seconds {
+            candidates.push(("minOfflineCrackSeconds", crack_seconds,
+                format!("Would pass if an offline crack time of {} were acceptable.", describe_duration(crack_seconds, FeedbackLanguage::English)),
+                PasswordPolicy { min_offline_crack_seconds: Some(crack_seconds), ..policy.clone() }));
+        }
+    }
+
+    let relaxations = candidates
+        .into_iter()
+        .filter(|(_, _, _, relaxed)| analyze_password(&password, relaxed, &[]).is_compliant)
+        .map(|(constraint, relaxed_value, message, _)| ConstraintRelaxation {
+            constraint: constraint.to_string(),
+            relaxed_value,
+            message,
+        })
+        .collect();
+
+    PolicyRelaxation { already_compliant: false, relaxations }
+}
+
+#[napi]
+/// Deterministic policy-compliant code for reproducible provisioning (e.g. recovery codes):
+/// the same `seed` and policy always give the same output. NOT for user passwords, anyone holding
+/// the seed can regenerate the code, so the seed must be kept as secret as the code itself.
+/// Seeds shorter than 16 bytes are rejected
+pub fn generate_from_seed(seed: Vec<u8>, policy: PasswordPolicy) -> Result<String> {
+    if seed.len() < MIN_SEED_LEN {
+        return Err(Error::from_reason(format!("seed must be at least {} bytes", MIN_SEED_LEN)));
+    }
+    // Hashing fixes the key size whatever the seed length
+    let mut rng = ChaCha20Rng::from_seed(Sha256::digest(&seed).into());
+    let length = (policy.min_length() as usize).max(GENERATED_MIN_LENGTH);
+    let all_chars: Vec<char> = GENERATOR_CLASSES.concat().chars().collect();
+
+    // Draws continue from the same stream, so retries are deterministic too
+    for _ in 0..MAX_GENERATE_ATTEMPTS {
+        // One from every class, then fill and shuffle
+        let mut chars: Vec<char> = GENERATOR_CLASSES
+            .iter()
+            .map(|class| *class.chars().collect::<Vec<char>>().choose(&mut rng).unwrap_or(&'x'))
+            .collect();
+        while chars.len() < length {
+            chars.push(*all_chars.choose(&mut rng).unwrap_or(&'x'));
+        }
+        chars.shuffle(&mut rng);
+
+        let candidate: String = chars.into_iter().collect();
+        if analyze_password(&candidate, &policy, &[]).is_compliant {
+            return Ok(candidate);
+        }
+    }
+    Err(Error::from_reason("Could not generate a password satisfying the policy"))
+}
+
+#[napi]
+/// The scoring constants currently in use, serialize it to pin them for `score_with_model_json`
+pub fn scoring_model() -> ScoringModel {
+    DEFAULT_SCORING_MODEL.clone()
+}
+
+#[napi]
+/// Name, size, version and content hash of every list compiled into the library, to check the
+/// built-in data is what's expected and spot changes across upgrades
+pub fn embedded_resources() -> Vec<ResourceInfo> {
+    [
+        ("common_passwords", COMMON_PASSWORDS),
+        ("padding_tokens", PADDING_TOKENS),
+        ("common_names", COMMON_NAMES),
+        ("common_phrases", COMMON_PHRASES),
+    ]
+    .into_iter()
+    .map(|(name, entries)| ResourceInfo {
+        name: name.to_string(),
+        entry_count: entries.len() as u32,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        sha256: hex::encode(Sha256::digest(entries.join("\n").as_bytes())),
+    })
+    .collect()
+}
+
+#[napi]
+/// Scores a password with a `ScoringModel` given as JSON (e.g. a pinned `scoring_model()` export)
+/// instead of the built-in one. A JSON missing a field or pinned at another `version` is rejected,
+/// so an upgrade that changes scoring surfaces as an error instead of silently mixing models
+pub fn score_with_model_json(password: String, model_json: String) -> Result<u32> {
+    let model: ScoringModel = serde_json::from_str(&model_json)
+        .map_err(|e| Error::from_reason(format!("Invalid scoring model JSON: {}", e)))?;
+    if model.version != SCORING_MODEL_VERSION {
+        return Err(Error::from_reason(format!(
+            "Scoring model version {} doesn't match the current version {}", model.version, SCORING_MODEL_VERSION)));
+    }
+
+    let password = strip_paste_artifacts(&password);
+    let pattern_analysis = analyze_patterns(&password, &PasswordPolicy::default());
+    let effective_entropy_bits = calculate_effective_entropy(&password, calculate_entropy(&pattern_analysis));
+    Ok(calculate_strength_score(&pattern_analysis, effective_entropy_bits, &model))
+}
+
+#[napi]
+/// Projected strength score if `additional_chars` random characters from the password's current
+/// character classes were appended, for "keep typing" hints. Random additions bring no new
+/// classes or patterns, so only length-driven scoring moves (the length tier, or entropy in `Entropy` mode)
+pub fn score_if_extended(password: String, additional_chars: u32, policy: PasswordPolicy) -> u32 {
+    let password = strip_paste_artifacts(&password);
+    let mut projected = analyze_patterns(&password, &policy);
+    projected.length = projected.length.saturating_add(additional_chars);
+    // Random additions also break up any alternating pattern, so no entropy discount applies
+    calculate_strength_score(&projected, calculate_entropy(&projected), &policy.scoring_model())
+}
+
+#[napi]
+/// Effective number of equally likely symbols the password draws from: the perplexity (2 to the
+/// Shannon entropy) of its character distribution. Eight distinct characters give 8, while a
+/// skewed "aaaab" gives about 1.65 despite using two symbols. 0 for an empty password
+pub fn effective_alphabet_size(password: String) -> f64 {
+    effective_alphabet(&password)
+}
+
+#[napi]
+/// Per grapheme, how much it adds to predictability from 0 (random-looking) to 1, for coloring
+/// each character in a strength visualization. Uses zxcvbn's matched spans (dictionary words,
+/// sequences, keyboard patterns, repeats, dates): a span scores 1 minus its guess bits per
+/// character relative to a random printable character, unmatched characters score 0.
+/// zxcvbn only evaluates the first 100 characters, so graphemes past that always score 0
+pub fn char_guessability(password: String) -> Vec<f64> {
+    let Ok(estimate) = zxcvbn::zxcvbn(&password, &[]) else {
+        return Vec::new(); // Blank password
     };
-    
-    if analysis.has_lowercase { score += 10; }
-    if analysis.has_uppercase { score += 10; }
-    if analysis.has_numbers { score += 15; }
-    if analysis.has_symbols { score += 20; }
-    
-    if analysis.repeated_chars > 0 { score = score.saturating_sub(10); }
-    if analysis.sequential_chars > 0 { score = score.saturating_sub(15); }
-    
-    std::cmp::min(score, 100)
+
+    let mut per_char = vec![0.0; password.chars().count()];
+    for span in estimate.sequence().iter().filter(|span| span.pattern != MatchPattern::BruteForce) {
+        let span_len = (span.j + 1 - span.i) as f64;
+        let bits_per_char = (span.guesses.unwrap_or(1).max(1) as f64).log2() / span_len;
+        let value = 1.0 - (bits_per_char / RANDOM_CHAR_BITS).clamp(0.0, 1.0);
+        per_char[span.i..=span.j].fill(value);
+    }
+
+    // A grapheme takes the highest value of its chars
+    let mut char_values = per_char.into_iter();
+    password
+        .graphemes(true)
+        .map(|grapheme| char_values.by_ref().take(grapheme.chars().count()).fold(0.0, f64::max))
+        .collect()
 }
 
-fn calculate_entropy(password: &str, analysis: &PatternAnalysis) -> f64 {
-    let mut charset_size = 0;
-    if analysis.has_lowercase { charset_size += 26; }
-    if analysis.has_uppercase { charset_size += 26; }
-    if analysis.has_numbers { charset_size += 10; }
-    if analysis.has_symbols { charset_size += 32; }
-    
-    let length = password.len() as f64;
-    length * (charset_size as f64).log2()
+#[napi]
+/// Checks the blocklist through combinations of reversal, digit/symbol affix stripping and leet
+/// normalization, returning the common base word that matched. Catches compound evasions like
+/// "1drowssap" (reversed "password1"). At most 12 candidate forms are tried per password
+pub fn normalized_common_match(password: String) -> Option<String> {
+    let lower_password = password.trim().to_lowercase();
+    let reversed: String = lower_password.chars().rev().collect();
+
+    for oriented in [lower_password.as_str(), reversed.as_str()] {
+        for stripped in [oriented, strip_affixes(oriented)] {
+            let candidates = [stripped.to_string(), leet_normalize(stripped, 'i'), leet_normalize(stripped, 'l')];
+            if let Some(base) = candidates.iter().find_map(|candidate| common_password_entry(candidate)) {
+                return Some(base.to_string());
+            }
+        }
+    }
+    None
 }
 
-fn generate_feedback(password: &str, analysis: &PatternAnalysis, score: u32) -> Vec<String> {
-    let mut feedback = Vec::new();
-    
-    if password.len() < 8 {
-        feedback.push("Password is too short (minimum 8 characters recommended).".to_string());
+#[napi]
+/// Password-change check for lazy reuse the stored hash can't catch: true when `candidate` is
+/// `previous` with letter case changed and nothing else, from a full inversion ("Secret" ->
+/// "sECRET") down to a single letter. An identical password is not a case change
+pub fn is_case_inversion(previous: String, candidate: String) -> bool {
+    let previous: Vec<char> = previous.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    if previous.len() != candidate.len() {
+        return false;
     }
-    if is_common_password(password) {
-        feedback.push("This password is too common and easy to guess.".to_string());
+
+    let same_letters = previous
+        .iter()
+        .zip(&candidate)
+        .all(|(p, c)| p == c || p.to_lowercase().eq(c.to_lowercase()));
+    same_letters && previous != candidate
+}
+
+#[napi]
+/// Password-change check against the user's previous plaintexts (only while they are transiently
+/// available): false when `candidate` is fewer than the policy's `min_edit_distance_from_previous`
+/// edits from any of them. Fails when the policy doesn't set the minimum
+pub fn check_edit_distance_from_previous(mut candidate: String, mut previous_plaintexts: Vec<String>, policy: PasswordPolicy) -> Result<bool> {
+    let min_distance = policy.min_edit_distance_from_previous
+        .ok_or_else(|| Error::from_reason("policy has no min_edit_distance_from_previous"))?;
+
+    let distinct = previous_plaintexts
+        .iter()
+        .all(|previous| strsim::levenshtein(previous, &candidate) >= min_distance as usize);
+
+    candidate.zeroize();
+    previous_plaintexts.iter_mut().for_each(Zeroize::zeroize);
+    Ok(distinct)
+}
+
+#[napi]
+/// Counts, across a batch, how many passwords failed each policy rule (by critical feedback code),
+/// for reporting which rules trip signups most. Compliant passwords add to no bucket
+pub fn failure_reason_histogram(passwords: Vec<String>, policy: PasswordPolicy) -> HashMap<String, u32> {
+    passwords
+        .par_iter()
+        .fold(HashMap::new, |mut histogram: HashMap<String, u32>, password| {
+            let analysis = analyze_password(password, &policy, &[]);
+            for item in analysis.feedback_items.into_iter().filter(|item| item.severity == Severity::Critical) {
+                *histogram.entry(item.code).or_insert(0) += 1;
+            }
+            histogram
+        })
+        .reduce(HashMap::new, |mut merged, histogram| {
+            for (code, count) in histogram {
+                *merged.entry(code).or_insert(0) += count;
+            }
+            merged
+        })
+}
+
+#[napi]
+/// Dry-runs a policy change over a batch of plaintexts (audit environments only): how many would
+/// start failing, start passing, or keep their compliance under `new_policy` versus `old_policy`
+pub fn policy_impact(passwords: Vec<String>, old_policy: PasswordPolicy, new_policy: PasswordPolicy) -> PolicyImpact {
+    let (newly_failing, newly_passing, unchanged) = passwords
+        .par_iter()
+        .map(|password| {
+            let was_compliant = analyze_password(password, &old_policy, &[]).is_compliant;
+            let is_compliant = analyze_password(password, &new_policy, &[]).is_compliant;
+            match (was_compliant, is_compliant) {
+                (true, false) => (1, 0, 0),
+                (false, true) => (0, 1, 0),
+                _ => (0, 0, 1),
+            }
+        })
+        .reduce(|| (0, 0, 0), |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2));
+
+    PolicyImpact { newly_failing, newly_passing, unchanged }
+}
+
+#[napi]
+/// Analyzes the batch in parallel (default policy) and returns the `k` weakest entries in
+/// ascending `strength_score` order, ties kept in input order. Entries carry the input index,
+/// never the plaintext, so the result can go to ops for account outreach
+pub fn weakest_passwords(passwords: Vec<String>, k: u32) -> Result<Vec<WeakEntry>> {
+    let policy = PasswordPolicy::default();
+    let mut entries: Vec<WeakEntry> = passwords
+        .par_iter()
+        .enumerate()
+        .map(|(index, password)| {
+            let analysis = analyze_password(password, &policy, &[]);
+            WeakEntry {
+                index: index as u32,
+                strength_score: analysis.strength_score,
+                top_reason: primary_weakness(&analysis, None).map(|(code, _)| code.to_string()),
+            }
+        })
+        .collect();
+
+    entries.sort_by_key(|entry| entry.strength_score);
+    entries.truncate(k as usize);
+    Ok(entries)
+}
+
+#[napi]
+/// Picks the single most important weakness for a one-line summary, in priority order:
+/// breached > common > too short > single character > sequential > low entropy > missing class.
+/// Breach status isn't part of the analysis, pass the HIBP count as `breach_count`
+pub fn primary_weakness_message(analysis: PasswordAnalysis, breach_count: Option<u32>) -> Option<String> {
+    primary_weakness(&analysis, breach_count).map(|(_, message)| message)
+}
+
+#[napi]
+/// Combines the strength analysis with breach exposure into a 0-100 risk score. Breaches push
+/// the score towards 100, scaled by how many times the password was seen and how recently, and
+/// by `config.breach_weight`
+pub fn assess_risk(analysis: PasswordAnalysis, breach: Option<BreachInfo>, config: Option<RiskConfig>) -> RiskAssessment {
+    let base_risk = 100.0 - analysis.strength_score.min(100) as f64;
+
+    let (breach_severity, breach_recency_weight) = match breach.filter(|breach| breach.count > 0) {
+        Some(breach) => {
+            // 1 sighting is half as bad as a million
+            let severity = (0.5 + (breach.count as f64).log10() / 12.0).min(1.0);
+            (severity, breach_recency_weight(breach.most_recent_year))
+        }
+        None => (0.0, 0.0),
+    };
+
+    let breach_push = breach_severity * breach_recency_weight;
+    let breach_weight = config
+        .and_then(|config| config.breach_weight)
+        .filter(|weight| weight.is_finite())
+        .unwrap_or(DEFAULT_BREACH_WEIGHT)
+        .clamp(0.0, 1.0);
+    // Below 0.5 the push shrinks to nothing, above it grows to a full push for any breach
+    let weighted_push = if breach_severity == 0.0 {
+        0.0
+    } else if breach_weight <= 0.5 {
+        breach_push * breach_weight * 2.0
+    } else {
+        breach_push + (1.0 - breach_push) * (breach_weight - 0.5) * 2.0
+    };
+
+    let risk = base_risk + (100.0 - base_risk) * weighted_push;
+    RiskAssessment {
+        risk_score: risk.round().clamp(0.0, 100.0) as u32,
+        breach_recency_weight,
     }
-    if analysis.sequential_chars > 0 {
-        feedback.push("Passwords must not contain sequential characters (e.g., 'abc', '123').".to_string());
+}
+
+#[napi]
+/// Coarse, non-reversible summary of an analysis for product analytics: bands and buckets
+/// instead of exact values, and weakness categories as booleans. Nothing in it (no exact length,
+/// substrings, matched terms or hashes) can be turned back into the password
+pub fn telemetry_record(analysis: PasswordAnalysis) -> TelemetryRecord {
+    let fired = |codes: &[&str]| analysis.feedback_items.iter().any(|item| codes.contains(&item.code.as_str()));
+    let patterns = &analysis.pattern_analysis;
+
+    let bucket = if patterns.length_bucketed { patterns.length } else { length_bucket_index(patterns.length) };
+    let length_bucket = ["<8", "8-11", "12-15", "16+"][(bucket as usize).min(3)];
+    let class_count = [patterns.has_lowercase, patterns.has_uppercase, patterns.has_numbers, patterns.has_symbols]
+        .into_iter()
+        .filter(|present| *present)
+        .count() as u32;
+
+    TelemetryRecord {
+        compliant: analysis.is_compliant,
+        score_band: analysis.strength_score / 20 * 20,
+        entropy_bucket: ((analysis.effective_entropy_bits.max(0.0) as u32) / 20 * 20).min(100),
+        length_bucket: length_bucket.to_string(),
+        class_count,
+        common_word: fired(&["COMMON_PASSWORD", "LEET_COMMON", "COMMON_PHRASE", "COMMON_NAME"]),
+        too_short: fired(&["TOO_SHORT"]),
+        sequential: patterns.sequential_chars > 0 || patterns.shift_keyboard_walk || patterns.interleaved_sequence,
+        repeated: patterns.repeated_chars > 0 || patterns.alternating_pattern,
+        personal_info: fired(&[
+            "IDENTITY_TRANSFORMED", "CONTAINS_USERNAME", "CONTAINS_EMAIL", "CONTAINS_NAME",
+            "CONTAINS_DOB", "CONTAINS_SERVICE_NAME", "LOOKS_LIKE_EMAIL",
+        ]),
+        predictable_case: patterns.only_first_capitalized || patterns.studly_caps,
+        blocklisted: patterns.contains_forbidden,
+        low_strength: fired(&["LOW_STRENGTH", "CRACKS_TOO_FAST"]),
     }
-    if !analysis.has_uppercase {
-        feedback.push("Consider adding uppercase letters for more strength.".to_string());
+}
+
+#[napi]
+/// Hashes a password securely using Argon2
+pub fn hash_password(password: String) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let argon2 = Argon2::default();
+    
+    match argon2.hash_password(password.as_bytes(), &salt) {
+        Ok(hash) => Ok(hash.to_string()),
+        Err(_) => Err(Error::from_reason("Failed to hash password")),
     }
-    if !analysis.has_numbers {
-        feedback.push("Adding numbers will make your password stronger.".to_string());
+}
+
+#[napi]
+/// Hashes with explicit Argon2id `params` and a random salt of `salt_len` bytes (default 16,
+/// allowed 8 to 48). Verification reads params and salt back from the PHC string, so
+/// `verify_password_hash` works unchanged
+pub fn hash_password_with_params(password: String, params: Argon2Params, salt_len: Option<u32>) -> Result<String> {
+    let salt_len = salt_len.map_or(argon2::RECOMMENDED_SALT_LEN, |len| len as usize);
+    if !(argon2::MIN_SALT_LEN..=MAX_SALT_LEN).contains(&salt_len) {
+        return Err(Error::from_reason(format!(
+            "salt_len must be between {} and {} bytes", argon2::MIN_SALT_LEN, MAX_SALT_LEN)));
     }
-    if !analysis.has_symbols {
-        feedback.push("Special characters like !@#$%^&* add significant security.".to_string());
+
+    let mut salt_bytes = vec![0u8; salt_len];
+    OsRng.fill_bytes(&mut salt_bytes);
+    let salt = SaltString::encode_b64(&salt_bytes)
+        .map_err(|e| Error::from_reason(format!("Failed to encode salt: {}", e)))?;
+
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params.to_argon2()?);
+    argon2
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|_| Error::from_reason("Failed to hash password"))
+}
+
+#[napi]
+// Verifies a plaintext password against a stored Argon2 hash
+pub fn verify_password_hash(password: String, hash: String) -> Result<bool> {
+    match PasswordHash::new(&hash) {
+        Ok(parsed_hash) => {
+            let argon2 = Argon2::default();
+            Ok(argon2.verify_password(password.as_bytes(), &parsed_hash).is_ok())
+        }
+        Err(_) => Ok(false), // If hash fails, it can't be valid
     }
-    if score < 75 {
-        feedback.push("For maximum security, use a password manager to generate long, random passwords.".to_string());
+}
+
+#[napi]
+/// Normalizes the password to `form` ("NFC" by default, or "NFKC") before Argon2 hashing and
+/// prefixes the PHC string with the form used, e.g. "nfc$$argon2id$...". The marker isn't secret;
+/// it pins the form for verification even if the default changes later
+pub fn hash_password_normalized(password: String, form: Option<String>) -> Result<String> {
+    let form = form.as_deref().map_or(Ok(NormalizationForm::Nfc), NormalizationForm::from_name)?;
+    let hash = hash_password(form.apply(&password))?;
+    Ok(format!("{}${}", form.marker(), hash))
+}
+
+#[napi]
+/// Verifies against a `hash_password_normalized` hash, normalizing with the form its marker names.
+/// Hashes without a marker are verified against the password as given. Fails on an unknown marker
+pub fn verify_password_normalized(password: String, stored_hash: String) -> Result<bool> {
+    match stored_hash.split_once('$') {
+        Some((marker, hash)) if !marker.is_empty() => {
+            let form = NormalizationForm::from_name(marker)?;
+            verify_password_hash(form.apply(&password), hash.to_string())
+        }
+        _ => verify_password_hash(password, stored_hash),
     }
-    
-    feedback
 }
 
-// Helpers
+#[napi(ts_return_type = "Promise<boolean>")]
+/// `verify_password_hash` padded with a sleep so the call takes at least `min_duration_ms`
+/// whether the password matches, mismatches or the hash is malformed. A verification that
+/// already ran past the envelope resolves straight away. Verification and the wait both run off
+/// the JS thread
+pub fn verify_password_fixed_time(password: String, hash: String, min_duration_ms: u32) -> AsyncTask<VerifyFixedTime> {
+    AsyncTask::new(VerifyFixedTime { password, hash, min_duration: Duration::from_millis(min_duration_ms as u64) })
+}
 
-fn is_common_password(password: &str) -> bool {
-    let lower_password = password.to_lowercase();
-    COMMON_PASSWORDS.contains(&lower_password.as_str())
+#[napi]
+/// True when a hash created at `created_at_ms` (Unix epoch millis) is more than `max_age_days`
+/// old, so callers can force a rehash on the next login even when its params are current.
+/// Timestamps in the future are never stale
+pub fn is_hash_stale(created_at_ms: i64, max_age_days: u32) -> bool {
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0);
+    let max_age_ms = max_age_days as i64 * 86_400_000;
+
+    now_ms.saturating_sub(created_at_ms) > max_age_ms
 }
 
-fn count_repeated_chars(password: &str) -> u32 {
-    let chars: Vec<char> = password.chars().collect();
-    let mut count = 0;
-    for window in chars.windows(3) {
-        if window[0] == window[1] && window[1] == window[2] {
-            count += 1;
+#[napi]
+/// Hashes a password with Argon2, feeding `pepper` in as Argon2's secret key input.
+/// The pepper is never stored in the PHC string, so the same pepper is needed to verify
+pub fn hash_password_with_pepper(password: String, pepper: Vec<u8>) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let argon2 = peppered_argon2(&pepper)?;
+
+    match argon2.hash_password(password.as_bytes(), &salt) {
+        Ok(hash) => Ok(hash.to_string()),
+        Err(_) => Err(Error::from_reason("Failed to hash password")),
+    }
+}
+
+#[napi]
+/// Verifies against a peppered hash by trying each candidate pepper in order, for rotating
+/// peppers on hashes that don't record which pepper produced them
+pub fn verify_with_pepper_rotation(password: String, hash: String, peppers: Vec<Vec<u8>>) -> Result<VerifyPepperResult> {
+    let parsed_hash = match PasswordHash::new(&hash) {
+        Ok(parsed_hash) => parsed_hash,
+        Err(_) => return Ok(VerifyPepperResult { valid: false, pepper_index: None }),
+    };
+
+    for (index, pepper) in peppers.iter().enumerate() {
+        let argon2 = peppered_argon2(pepper)?;
+        if argon2.verify_password(password.as_bytes(), &parsed_hash).is_ok() {
+            return Ok(VerifyPepperResult { valid: true, pepper_index: Some(index as u32) });
         }
     }
-    count
+
+    Ok(VerifyPepperResult { valid: false, pepper_index: None })
 }
 
-fn count_sequential_chars(password: &str) -> u32 {
-    let lower_password = password.to_lowercase();
-    COMMON_PATTERNS_RE.iter().filter(|re| re.is_match(&lower_password)).count() as u32
-}
\ No newline at end of file
+#[napi]
+// Hashes a large number of passwords in parallel, see par_map_chunked for chunk_size
+pub fn batch_hash_passwords(passwords: Vec<String>, chunk_size: Option<u32>) -> Result<HashMap<String, String>> {
+    let results: HashMap<String, String> = par_map_chunked(&passwords, chunk_size, |password| {
+        let hash = hash_password(password.clone()).unwrap_or_else(|_| "ERROR".to_string());
+        (password.clone(), hash)
+    })
+    .into_iter()
+    .collect();
+    
+    Ok(results)
+}
+
+#[napi]
+/// Unkeyed SHA-256 (hex) of every password in input order, plus how many distinct fingerprints
+/// the batch has and how often the most reused one appears, for dedup and reuse audits over an
+/// export. Unsalted, so keep the output as sensitive as the passwords; see `reuse_fingerprint`
+/// for a keyed variant
+pub fn batch_fingerprint_sha256(passwords: Vec<String>) -> Result<FingerprintBatch> {
+    let fingerprints: Vec<String> = passwords
+        .par_iter()
+        .map(|password| hex::encode(Sha256::digest(password.as_bytes())))
+        .collect();
+
+    let mut counts: HashMap<&str, u32> = HashMap::new();
+    for fingerprint in &fingerprints {
+        *counts.entry(fingerprint.as_str()).or_insert(0) += 1;
+    }
+    let distinct_count = counts.len() as u32;
+    let max_reuse_count = counts.values().copied().max().unwrap_or(0);
+
+    Ok(FingerprintBatch { fingerprints, distinct_count, max_reuse_count })
+}
+
+#[napi]
+/// Groups an export's passwords by SHA-256 fingerprint and returns the groups shared by two or
+/// more inputs (e.g. a default password set on many accounts), ordered by their first member.
+/// Like `batch_fingerprint_sha256` the fingerprints are unsalted, so treat them as sensitive
+pub fn find_reuse_clusters(passwords: Vec<String>) -> Vec<ReuseCluster> {
+    let fingerprints: Vec<String> = passwords
+        .par_iter()
+        .map(|password| hex::encode(Sha256::digest(password.as_bytes())))
+        .collect();
+
+    let mut members: HashMap<String, Vec<u32>> = HashMap::new();
+    for (index, fingerprint) in fingerprints.into_iter().enumerate() {
+        members.entry(fingerprint).or_default().push(index as u32);
+    }
+
+    let mut clusters: Vec<ReuseCluster> = members
+        .into_iter()
+        .filter(|(_, member_indices)| member_indices.len() >= 2)
+        .map(|(fingerprint, member_indices)| ReuseCluster {
+            fingerprint,
+            count: member_indices.len() as u32,
+            member_indices,
+        })
+        .collect();
+    clusters.sort_by_key(|cluster| cluster.member_indices[0]);
+    clusters
+}
+
+#[napi]
+/// Rehashes already-verified plaintexts into `algorithm` ("argon2" or "bcrypt") for bulk migrations.
+/// `params_json` holds `Argon2Params` or `BcryptParams` (empty for defaults). Output order matches
+/// the input, and each plaintext is zeroized once hashed
+pub fn batch_rehash(pairs: Vec<VerifiedPassword>, algorithm: String, params_json: String) -> Result<Vec<String>> {
+    let scheme = parse_hash_scheme(&algorithm, &params_json)?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(rayon::current_num_threads().min(MAX_REHASH_THREADS))
+        .build()
+        .map_err(|e| Error::from_reason(format!("Failed to start rehash pool: {}", e)))?;
+
+    pool.install(|| {
+        pairs
+            .into_par_iter()
+            .map(|mut pair| {
+                let hash = scheme.hash(pair.password.as_bytes());
+                pair.password.zeroize();
+                hash
+            })
+            .collect()
+    })
+}
+
+#[napi]
+/// Verifies against a stored Argon2 or bcrypt hash (detected from its prefix) and, when it
+/// matches but was made with a different algorithm than `target_algo`, returns a replacement hash
+/// in the target algorithm (`target_params_json` as for `batch_rehash`) to store instead. Drives
+/// an algorithm migration one login at a time; params of a same-algorithm hash aren't compared
+pub fn verify_and_migrate_algo(password: String, stored_hash: String, target_algo: String, target_params_json: String) -> Result<MigrateResult> {
+    let mut password = password;
+    let target = parse_hash_scheme(&target_algo, &target_params_json)?;
+    let stored_algo = hash_algorithm(&stored_hash)
+        .ok_or_else(|| Error::from_reason("Unrecognized stored hash format"))?;
+
+    let valid = match stored_algo {
+        "argon2" => PasswordHash::new(&stored_hash)
+            .map(|parsed_hash| Argon2::default().verify_password(password.as_bytes(), &parsed_hash).is_ok())
+            .unwrap_or(false),
+        _ => bcrypt::verify(password.as_bytes(), &stored_hash).unwrap_or(false),
+    };
+    let new_hash = if valid && stored_algo != target.algorithm() {
+        Some(target.hash(password.as_bytes())?)
+    } else {
+        None
+    };
+    password.zeroize();
+
+    Ok(MigrateResult { valid, new_hash })
+}
+
+#[napi]
+/// Returns the per-hash Argon2 memory cost (KiB) that keeps `max_concurrent` simultaneous
+/// hashes within `total_ram_mib`. The budget is divided by `safety_factor` (values below 1
+/// are treated as 1) to leave headroom, and the result is kept within Argon2's memory limits.
+pub fn memory_per_hash_for_budget(total_ram_mib: u32, max_concurrent: u32, safety_factor: f64) -> u32 {
+    let safety_factor = if safety_factor.is_finite() && safety_factor >= 1.0 { safety_factor } else { 1.0 };
+    let budget_kib = total_ram_mib as f64 * 1024.0;
+    let per_hash_kib = budget_kib / (max_concurrent.max(1) as f64 * safety_factor);
+
+    (per_hash_kib.floor() as u32).clamp(Params::MIN_M_COST, Params::MAX_M_COST)
+}
+
+#[napi]
+/// Relative cost of an Argon2 profile, for "B is 2.3x A" comparisons without benchmarking:
+/// memory (MiB) x iterations, i.e. MiB of memory filled per hash. Parallelism isn't a factor, lanes
+/// split the same memory so total CPU work is unchanged (only wall time drops given free cores)
+pub fn argon2_work_units(params: Argon2Params) -> f64 {
+    params.memory_kib as f64 / 1024.0 * params.iterations as f64
+}
+
+#[napi]
+/// Keyed fingerprint for spotting the same password across sibling services: HMAC-SHA256 of the
+/// password under the shared `app_salt`, hex encoded. Not a storage hash (no work factor), and
+/// irreversible without the key, so fingerprints are only comparable between services sharing it
+pub fn reuse_fingerprint(password: String, app_salt: Vec<u8>) -> Result<String> {
+    if app_salt.is_empty() {
+        return Err(Error::from_reason("app_salt must not be empty"));
+    }
+    Ok(hex::encode(hmac_sha256(&app_salt, password.as_bytes())))
+}
+
+#[napi]
+/// Lookup key for our internal breach corpus: HMAC-SHA256 of the password under `corpus_key`,
+/// hex encoded, so the corpus is stored keyed by the same HMAC rather than a plaintext-derived hash.
+/// Keep `corpus_key` in the secrets store and never reuse the reuse-fingerprint salt; rotating it
+/// means re-keying the whole corpus, since old keys no longer match
+pub fn breach_lookup_key(password: String, corpus_key: Vec<u8>) -> Result<String> {
+    if corpus_key.is_empty() {
+        return Err(Error::from_reason("corpus_key must not be empty"));
+    }
+    Ok(hex::encode(hmac_sha256(&corpus_key, password.as_bytes())))
+}
+
+#[napi]
+// hash password with sha1 for Have I Been Pwned
+pub fn hash_password_sha1(password: String) -> Result<String> {
+    let mut hasher = Sha1::new();
+    hasher.update(password.as_bytes());
+    let result = hasher.finalize();
+    Ok(hex::encode(result).to_uppercase())
+}
+
+fn analyze_password(password: &str, policy: &PasswordPolicy, identity_terms: &[String]) -> PasswordAnalysis {
+    analyze_password_in_context(password, policy, identity_terms, None)
+}
+
+// context carries the already-validated birth date alongside the raw context
+fn analyze_password_in_context(
+    password: &str,
+    policy: &PasswordPolicy,
+    identity_terms: &[String],
+    context: Option<(&UserContext, Option<BirthDate>)>,
+) -> PasswordAnalysis {
+    let cleaned = strip_paste_artifacts(password);
+    let had_paste_artifacts = cleaned.len() != password.len();
+    let password = cleaned.as_str();
+    
+    let mut pattern_analysis = analyze_patterns(password, policy);
+    pattern_analysis.had_paste_artifacts = had_paste_artifacts;
+    pattern_analysis.identity_transformed = is_transformed_identity(password, identity_terms);
+    pattern_analysis.contains_dob = context.and_then(|(_, birth_date)| birth_date)
+        .is_some_and(|(year, month, day)| date_forms(year, month, day).iter().any(|form| password.contains(form.as_str())));
+    let scoring_model = policy.scoring_model();
+    let entropy_bits = calculate_entropy(&pattern_analysis);
+    let effective_entropy_bits = calculate_effective_entropy(password, entropy_bits);
+    let strength_score = calculate_strength_score(&pattern_analysis, effective_entropy_bits, &scoring_model);
+    
+    let padding = split_padding(password).map(|(token, base)| {
+        let base_analysis = analyze_patterns(base, policy);
+        let base_entropy_bits = calculate_effective_entropy(base, calculate_entropy(&base_analysis));
+        (token.to_string(), calculate_strength_score(&base_analysis, base_entropy_bits, &scoring_model), is_common_password(base))
+    });
+    
+    let mut feedback_items = generate_feedback(password, &pattern_analysis, strength_score, policy);
+    if let Some((token, base_score, base_is_common)) = &padding {
+        if *base_score < policy.min_strength_score() || *base_is_common {
+            feedback_items.push(FeedbackItem::new("WEAK_BASE_WITH_PADDING", Severity::Warning,
+                format!("Ignoring the '{}' padding, the rest of your password is weak.", token)));
+        }
+    }
+    if let Some((context, _)) = context {
+        feedback_items.extend(context_feedback(password, context, pattern_analysis.contains_dob));
+    }
+    // Stable sort, so items of equal severity keep the fixed order the checks run in
+    feedback_items.sort_by_key(|item| item.severity);
+    let feedback = feedback_items.iter().map(|item| item.message.clone()).collect();
+    
+    // Any critical item is a policy violation
+    let is_compliant = !feedback_items.iter().any(|item| item.severity == Severity::Critical);
+    
+    if policy.bucket_length.unwrap_or(false) {
+        pattern_analysis.length = length_bucket_index(pattern_analysis.length);
+        pattern_analysis.length_bucketed = true;
+    }
+    let mut analysis = PasswordAnalysis {
+        is_compliant,
+        strength_score,
+        entropy_bits,
+        effective_entropy_bits,
+        distribution_entropy_bits: password.chars().count() as f64 * effective_alphabet(password).max(1.0).log2(),
+        pattern_analysis,
+        base_strength_score: padding.as_ref().map(|(_, base_score, _)| *base_score),
+        padding_token: padding.map(|(token, _, _)| token),
+        feedback,
+        feedback_items,
+        result_hash: String::new(),
+        analysis_time_ms: 0,
+    };
+    analysis.result_hash = analysis_result_hash(&analysis);
+    analysis
+}
+
+fn analyze_patterns(password: &str, policy: &PasswordPolicy) -> PatternAnalysis {
+    let (pattern_region, _) = pattern_region(password, policy);
+    let forbidden_match = find_forbidden_substring(password);
+    PatternAnalysis {
+        has_uppercase: password.chars().any(|c| c.is_uppercase()),
+        has_lowercase: password.chars().any(|c| c.is_lowercase()),
+        has_numbers: password.chars().any(|c| c.is_numeric()),
+        has_symbols: password.chars().any(|c| !c.is_alphanumeric()),
+        has_non_ascii: !password.is_ascii(),
+        length: password.len() as u32,
+        length_bucketed: false,
+        repeated_chars: count_repeated_chars(pattern_region),
+        sequential_chars: count_sequential_chars(pattern_region, policy.exempts_numeric(password)),
+        longest_class_run: longest_class_run(password).1,
+        identity_transformed: false,
+        contains_dob: false,
+        had_paste_artifacts: false,
+        alternating_pattern: alternating_unit_len(password).is_some(),
+        interleaved_sequence: interleaved_sequence_len(password).is_some(),
+        only_first_capitalized: is_only_first_capitalized(password),
+        studly_caps: is_studly_caps(password),
+        shift_keyboard_walk: has_shift_keyboard_walk(pattern_region),
+        contains_common_name: is_common_name_based(password),
+        concatenated_phrase: is_concatenated_phrase(password),
+        leet_common_base: leet_common_base(password).map(str::to_string),
+        contains_banned_sequence: find_banned_sequence(password, policy.banned_sequences.as_deref().unwrap_or_default()),
+        contains_forbidden: forbidden_match.is_some(),
+        forbidden_match,
+    }
+}
+
+fn calculate_strength_score(analysis: &PatternAnalysis, effective_entropy_bits: f64, model: &ScoringModel) -> u32 {
+    if model.mode == ScoringMode::Entropy {
+        let target_bits = model.entropy_target_bits.max(f64::EPSILON);
+        let score = (effective_entropy_bits / target_bits * model.max_score as f64).round();
+        return (score.max(0.0) as u32).min(model.max_score);
+    }
+
+    let mut score = 0u32;
+    
+    score += model.length_tiers
+        .iter()
+        .filter(|tier| analysis.length >= tier.min_length)
+        .max_by_key(|tier| tier.min_length)
+        .map_or(0, |tier| tier.points);
+    
+    if analysis.studly_caps {
+        score += model.studly_case_bonus;
+    } else {
+        if analysis.has_lowercase { score += model.lowercase_bonus; }
+        if analysis.only_first_capitalized {
+            score += model.predictable_uppercase_bonus;
+        } else if analysis.has_uppercase {
+            score += model.uppercase_bonus;
+        }
+    }
+    if analysis.has_numbers { score += model.number_bonus; }
+    if analysis.has_symbols { score += model.symbol_bonus; }
+    
+    if analysis.repeated_chars > 0 { score = score.saturating_sub(model.repeat_penalty); }
+    if analysis.sequential_chars > 0 { score = score.saturating_sub(model.sequence_penalty); }
+    if analysis.shift_keyboard_walk { score = score.saturating_sub(model.shift_walk_penalty); }
+    
+    std::cmp::min(score, model.max_score)
+}
+
+fn calculate_entropy(analysis: &PatternAnalysis) -> f64 {
+    let mut charset_size = 0;
+    if analysis.has_lowercase { charset_size += 26; }
+    if analysis.has_uppercase { charset_size += 26; }
+    if analysis.has_numbers { charset_size += 10; }
+    if analysis.has_symbols { charset_size += 32; }
+    
+    let length = analysis.length as f64;
+    length * (charset_size as f64).log2()
+}
+
+// Perplexity of the character distribution, 2 to the Shannon entropy in bits; 0 for "".
+// Counts are summed in char order so the float result is the same on every run
+fn effective_alphabet(password: &str) -> f64 {
+    let mut counts: BTreeMap<char, u32> = BTreeMap::new();
+    for c in password.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let total: u32 = counts.values().sum();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let shannon_bits: f64 = counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum();
+    shannon_bits.exp2()
+}
+
+fn calculate_effective_entropy(password: &str, entropy_bits: f64) -> f64 {
+    let length = password.chars().count();
+    match alternating_unit_len(password) {
+        // Only the unit and how often it repeats are actually chosen
+        Some(unit_len) => {
+            let bits_per_char = entropy_bits / password.len() as f64;
+            unit_len as f64 * bits_per_char + (length as f64 / unit_len as f64).log2()
+        }
+        None => match interleaved_sequence_len(password) {
+            // Past the first pair only the direction and how far the run goes are chosen
+            Some(run_len) => {
+                let bits_per_char = entropy_bits / password.len() as f64;
+                entropy_bits - (run_len - 2) as f64 * bits_per_char + 1.0 + (run_len as f64 / 2.0).log2()
+            }
+            None => entropy_bits,
+        },
+    }
+}
+
+fn generate_feedback(password: &str, analysis: &PatternAnalysis, score: u32, policy: &PasswordPolicy) -> Vec<FeedbackItem> {
+    let mut feedback = Vec::new();
+    
+    if password.len() < policy.min_length() as usize {
+        feedback.push(FeedbackItem::new("TOO_SHORT", Severity::Critical,
+            format!("Password is too short (minimum {} characters recommended).", policy.min_length())));
+    }
+    if is_common_password(password) {
+        feedback.push(FeedbackItem::new("COMMON_PASSWORD", Severity::Critical,
+            "This password is too common and easy to guess."));
+    }
+    if let Some(base) = &analysis.leet_common_base {
+        feedback.push(FeedbackItem::new("LEET_COMMON", Severity::Critical,
+            format!("This is just '{}' with look-alike substitutions, which attackers try automatically.", base)));
+    }
+    if analysis.concatenated_phrase {
+        feedback.push(FeedbackItem::new("COMMON_PHRASE", Severity::Critical,
+            "This is a well-known phrase with the spaces removed, attackers try those early."));
+    }
+    if analysis.sequential_chars > 0 {
+        feedback.push(FeedbackItem::new("SEQUENTIAL_CHARS", Severity::Critical,
+            "Passwords must not contain sequential characters (e.g., 'abc', '123')."));
+    }
+    if policy.exempts_numeric(password) {
+        if analysis.repeated_chars > 0 {
+            feedback.push(FeedbackItem::new("REPEATED_CHARS", Severity::Critical,
+                "Avoid repeating the same digit three or more times in a row."));
+        }
+    } else if is_numeric_only(password) {
+        feedback.push(FeedbackItem::new("NUMERIC_ONLY", Severity::Critical,
+            "Password can't be made of numbers only."));
+    }
+    if score < policy.min_strength_score() && !policy.exempts_numeric(password) {
+        feedback.push(FeedbackItem::new("LOW_STRENGTH", Severity::Critical,
+            "Password is not strong enough, add length or more kinds of characters."));
+    }
+    if let Some(sequence) = &analysis.contains_banned_sequence {
+        feedback.push(FeedbackItem::new("BANNED_SEQUENCE", Severity::Critical,
+            format!("Password must not contain the sequence '{}'.", sequence)));
+    }
+    if let Some(term) = &analysis.forbidden_match {
+        feedback.push(FeedbackItem::new("FORBIDDEN_SUBSTRING", Severity::Critical,
+            format!("Password must not contain '{}'.", term)));
+    }
+    if analysis.identity_transformed {
+        feedback.push(FeedbackItem::new("IDENTITY_TRANSFORMED", Severity::Critical,
+            "Password is your username or personal details reversed or shifted around."));
+    }
+    if let Some(max_run) = policy.max_class_run {
+        if analysis.longest_class_run > max_run {
+            feedback.push(FeedbackItem::new("LONG_CLASS_RUN", Severity::Critical,
+                format!("Avoid more than {} characters of the same kind in a row (e.g., '12345').", max_run)));
+        }
+    }
+    if policy.forbid_current_year.unwrap_or(false) && password.contains(&current_year().to_string()) {
+        feedback.push(FeedbackItem::new("CONTAINS_CURRENT_YEAR", Severity::Critical,
+            "Password must not contain the current year."));
+    }
+    if policy.forbid_email_format.unwrap_or(false) && EMAIL_RE.is_match(password) {
+        feedback.push(FeedbackItem::new("LOOKS_LIKE_EMAIL", Severity::Critical,
+            "Password must not be an email address."));
+    }
+    if policy.ascii_only.unwrap_or(false) && analysis.has_non_ascii {
+        feedback.push(FeedbackItem::new("NON_ASCII_FORBIDDEN", Severity::Critical,
+            "Password may only use ASCII characters (no accented letters or emoji)."));
+    }
+    if let Some(min_seconds) = policy.min_offline_crack_seconds {
+        let crack_seconds = estimate_offline_crack_seconds(password);
+        if crack_seconds < min_seconds {
+            feedback.push(FeedbackItem::new("CRACKS_TOO_FAST", Severity::Critical,
+                format!("Password could be cracked offline in {}, make it longer or less predictable.", describe_duration(crack_seconds, FeedbackLanguage::English))));
+        }
+    }
+    if policy.require_interior_symbol.unwrap_or(false) && !has_interior_symbol(password) {
+        feedback.push(FeedbackItem::new("EXTERIOR_SYMBOL_ONLY", Severity::Critical,
+            "Include a special character somewhere in the middle, not just at the start or end."));
+    }
+    if analysis.only_first_capitalized {
+        feedback.push(FeedbackItem::new("PREDICTABLE_CAPITALIZATION", Severity::Warning,
+            "Capitalizing only the first letter is the first thing attackers try, put capitals elsewhere too."));
+    }
+    if analysis.contains_common_name {
+        feedback.push(FeedbackItem::new("COMMON_NAME", Severity::Warning,
+            "Passwords built around a first name are among the first guesses, even with numbers added."));
+    }
+    if analysis.shift_keyboard_walk {
+        feedback.push(FeedbackItem::new("SHIFT_KEYBOARD_WALK", Severity::Warning,
+            "Walking along the keyboard with shift held (e.g. '!QAZ2wsx') is an easily guessed pattern."));
+    }
+    if analysis.studly_caps {
+        feedback.push(FeedbackItem::new("PREDICTABLE_CASE_PATTERN", Severity::Warning,
+            "Alternating upper and lower case is a pattern attackers try, vary capitals irregularly."));
+    }
+    if !analysis.has_uppercase {
+        feedback.push(FeedbackItem::new("MISSING_UPPERCASE", Severity::Info,
+            "Consider adding uppercase letters for more strength."));
+    }
+    if !analysis.has_numbers {
+        feedback.push(FeedbackItem::new("MISSING_NUMBER", Severity::Info,
+            "Adding numbers will make your password stronger."));
+    }
+    if !analysis.has_symbols {
+        feedback.push(FeedbackItem::new("MISSING_SYMBOL", Severity::Info,
+            "Special characters like !@#$%^&* add significant security."));
+    }
+    if policy.encourage_non_ascii.unwrap_or(false) && !analysis.has_non_ascii {
+        feedback.push(FeedbackItem::new("ADD_NON_ASCII", Severity::Info,
+            "Adding an accented letter or other non-ASCII character makes your password harder to guess."));
+    }
+    if score < 75 {
+        feedback.push(FeedbackItem::new("USE_PASSWORD_MANAGER", Severity::Info,
+            "For maximum security, use a password manager to generate long, random passwords."));
+    }
+    
+    feedback
+}
+
+fn context_feedback(password: &str, context: &UserContext, contains_dob: bool) -> Vec<FeedbackItem> {
+    let mut feedback = Vec::new();
+    let lower_password = password.to_lowercase();
+    // Short terms would match too much by accident, same cutoff as the identity check
+    let contains_term = |term: &str| {
+        let term = term.trim().to_lowercase();
+        term.chars().count() >= 3 && lower_password.contains(&term)
+    };
+
+    if context.username.as_deref().is_some_and(contains_term) {
+        feedback.push(FeedbackItem::new("CONTAINS_USERNAME", Severity::Critical,
+            "Password must not contain your username."));
+    }
+    if let Some(email) = &context.email {
+        let local_part = email.split_once('@').map_or(email.as_str(), |(local_part, _)| local_part);
+        if contains_term(email) || contains_term(local_part) {
+            feedback.push(FeedbackItem::new("CONTAINS_EMAIL", Severity::Critical,
+                "Password must not contain your email address or its name part."));
+        }
+    }
+    if context.full_name.as_deref().is_some_and(|name| name.split_whitespace().any(contains_term)) {
+        feedback.push(FeedbackItem::new("CONTAINS_NAME", Severity::Critical,
+            "Password must not contain your name."));
+    }
+    if contains_dob {
+        feedback.push(FeedbackItem::new("CONTAINS_DOB", Severity::Critical,
+            "Password must not contain your date of birth."));
+    }
+    if let Some(term) = context.service_terms.iter().flatten().find(|term| contains_term(term)) {
+        feedback.push(FeedbackItem::new("CONTAINS_SERVICE_NAME", Severity::Critical,
+            format!("Password must not contain '{}'.", term.trim())));
+    }
+
+    feedback
+}
+
+// Spanish wording per feedback code, None keeps the English message
+fn spanish_message(code: &str, password: &str, patterns: &PatternAnalysis, padding_token: Option<&str>, policy: &PasswordPolicy) -> Option<String> {
+    let message = match code {
+        "TOO_SHORT" => format!("La contraseña es demasiado corta (se recomiendan al menos {} caracteres).", policy.min_length()),
+        "COMMON_PASSWORD" => "Esta contraseña es demasiado común y fácil de adivinar.".to_string(),
+        "LEET_COMMON" => format!(
+            "Es solo '{}' con sustituciones de caracteres parecidos, que los atacantes prueban automáticamente.",
+            patterns.leet_common_base.as_deref()?),
+        "COMMON_PHRASE" => "Es una frase conocida sin espacios, los atacantes las prueban muy pronto.".to_string(),
+        "SEQUENTIAL_CHARS" => "La contraseña no debe contener caracteres secuenciales (p. ej., 'abc', '123').".to_string(),
+        "REPEATED_CHARS" => "Evita repetir el mismo dígito tres o más veces seguidas.".to_string(),
+        "NUMERIC_ONLY" => "La contraseña no puede estar formada solo por números.".to_string(),
+        "LOW_STRENGTH" => "La contraseña no es lo bastante fuerte, añade longitud o más tipos de caracteres.".to_string(),
+        "BANNED_SEQUENCE" => format!("La contraseña no debe contener la secuencia '{}'.", patterns.contains_banned_sequence.as_deref()?),
+        "FORBIDDEN_SUBSTRING" => format!("La contraseña no debe contener '{}'.", patterns.forbidden_match.as_deref()?),
+        "IDENTITY_TRANSFORMED" => "La contraseña es tu usuario o tus datos personales invertidos o desplazados.".to_string(),
+        "LONG_CLASS_RUN" => format!(
+            "Evita más de {} caracteres del mismo tipo seguidos (p. ej., '12345').", policy.max_class_run?),
+        "CONTAINS_CURRENT_YEAR" => "La contraseña no debe contener el año actual.".to_string(),
+        "LOOKS_LIKE_EMAIL" => "La contraseña no debe ser una dirección de correo electrónico.".to_string(),
+        "NON_ASCII_FORBIDDEN" => "La contraseña solo puede usar caracteres ASCII (sin letras acentuadas ni emojis).".to_string(),
+        "CRACKS_TOO_FAST" => format!(
+            "La contraseña se podría descifrar sin conexión en {}, hazla más larga o menos predecible.",
+            describe_duration(estimate_offline_crack_seconds(password), FeedbackLanguage::Spanish)),
+        "EXTERIOR_SYMBOL_ONLY" => "Incluye un carácter especial en medio, no solo al principio o al final.".to_string(),
+        "PREDICTABLE_CAPITALIZATION" => "Poner en mayúscula solo la primera letra es lo primero que prueban los atacantes, usa mayúsculas en otras posiciones.".to_string(),
+        "COMMON_NAME" => "Las contraseñas basadas en un nombre de pila están entre las primeras que se prueban, aunque lleven números.".to_string(),
+        "SHIFT_KEYBOARD_WALK" => "Recorrer el teclado con Mayús pulsada (p. ej., '!QAZ2wsx') es un patrón fácil de adivinar.".to_string(),
+        "PREDICTABLE_CASE_PATTERN" => "Alternar mayúsculas y minúsculas es un patrón que prueban los atacantes, varía las mayúsculas de forma irregular.".to_string(),
+        "WEAK_BASE_WITH_PADDING" => format!("Sin el relleno '{}', el resto de tu contraseña es débil.", padding_token?),
+        "MISSING_UPPERCASE" => "Considera añadir letras mayúsculas para más seguridad.".to_string(),
+        "MISSING_NUMBER" => "Añadir números hará tu contraseña más fuerte.".to_string(),
+        "MISSING_SYMBOL" => "Los caracteres especiales como !@#$%^&* añaden mucha seguridad.".to_string(),
+        "ADD_NON_ASCII" => "Añadir una letra acentuada u otro carácter no ASCII hace tu contraseña más difícil de adivinar.".to_string(),
+        "USE_PASSWORD_MANAGER" => "Para la máxima seguridad, usa un gestor de contraseñas que genere contraseñas largas y aleatorias.".to_string(),
+        _ => return None,
+    };
+    Some(message)
+}
+
+// Helpers
+
+// Copy-paste leftovers that inflate length and fake a symbol class. The zero-width joiner is
+// kept since it's a legitimate part of emoji sequences
+fn strip_paste_artifacts(password: &str) -> String {
+    password
+        .chars()
+        .filter(|c| !matches!(c, '\u{200B}' | '\u{200C}' | '\u{2060}' | '\u{FEFF}'))
+        .collect::<String>()
+        .trim_end_matches(char::is_control)
+        .to_string()
+}
+
+// Whitespace is dropped on both sides, so spaced and unspaced forms both match
+fn is_concatenated_phrase(password: &str) -> bool {
+    let squashed: String = password.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    COMMON_PHRASES.iter().any(|phrase| phrase.replace(' ', "") == squashed)
+}
+
+fn is_common_password(password: &str) -> bool {
+    // Padding like "    admin" shouldn't dodge the blocklist, length is still reported raw
+    let lower_password = password.trim().to_lowercase();
+    COMMON_PASSWORDS.contains(&lower_password.as_str())
+}
+
+// (token, base) for the longest padding token at the end, or failing that the start
+fn split_padding(password: &str) -> Option<(&'static str, &str)> {
+    let longest = |matches: &dyn Fn(&str) -> bool| {
+        PADDING_TOKENS.iter().copied().filter(|token| matches(token)).max_by_key(|token| token.len())
+    };
+    let long_enough = |base: &str| base.chars().count() >= MIN_PADDED_BASE_LEN;
+
+    if let Some(token) = longest(&|token| password.ends_with(token)) {
+        let base = &password[..password.len() - token.len()];
+        if long_enough(base) {
+            return Some((token, base));
+        }
+    }
+    if let Some(token) = longest(&|token| password.starts_with(token)) {
+        let base = &password[token.len()..];
+        if long_enough(base) {
+            return Some((token, base));
+        }
+    }
+    None
+}
+
+fn common_password_entry(candidate: &str) -> Option<&'static str> {
+    COMMON_PASSWORDS.iter().copied().find(|common| *common == candidate)
+}
+
+// Drops leading/trailing digits and symbols, "!!password123" -> "password"
+fn strip_affixes(password: &str) -> &str {
+    password.trim_matches(|c: char| !c.is_alphabetic())
+}
+
+fn length_bucket_index(length: u32) -> u32 {
+    match length {
+        0..=7 => 0,
+        8..=11 => 1,
+        12..=15 => 2,
+        _ => 3,
+    }
+}
+
+// Digit-only ways of writing a date, 8-digit forms first, then 2-digit years, then the 4-digit pieces
+fn date_forms(year: u32, month: u32, day: u32) -> Vec<String> {
+    let short_year = year % 100;
+    vec![
+        format!("{:04}{:02}{:02}", year, month, day),
+        format!("{:02}{:02}{:04}", day, month, year),
+        format!("{:02}{:02}{:04}", month, day, year),
+        format!("{:02}{:02}{:02}", day, month, short_year),
+        format!("{:02}{:02}{:02}", month, day, short_year),
+        format!("{:02}{:02}", month, day),
+        format!("{:02}{:02}", day, month),
+        format!("{:04}", year),
+    ]
+}
+
+// Canonical form is the JSON value with sorted keys, minus the timing field and the hash itself
+fn analysis_result_hash(analysis: &PasswordAnalysis) -> String {
+    let mut value = serde_json::to_value(analysis).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("analysis_time_ms");
+        fields.remove("result_hash");
+    }
+    hex::encode(Sha256::digest(value.to_string().as_bytes()))
+}
+
+// "Michael1", "j3ssica", "!Amanda99". Affixes are stripped both before and after leet
+// normalization since digits can be either padding or substituted letters
+fn is_common_name_based(password: &str) -> bool {
+    let lower_password = password.to_lowercase();
+    let stripped = strip_affixes(&lower_password);
+
+    let mut candidates = vec![stripped.to_string()];
+    for one_as in ['i', 'l'] {
+        candidates.push(leet_normalize(stripped, one_as));
+        candidates.push(strip_affixes(&leet_normalize(&lower_password, one_as)).to_string());
+    }
+    candidates.iter().any(|candidate| COMMON_NAMES.contains(&candidate.as_str()))
+}
+
+// Only when a substitution actually happened, plain common passwords are COMMON_PASSWORD's job
+fn leet_common_base(password: &str) -> Option<&'static str> {
+    let lower_password = password.trim().to_lowercase();
+    ['i', 'l']
+        .into_iter()
+        .map(|one_as| leet_normalize(&lower_password, one_as))
+        .filter(|normalized| *normalized != lower_password)
+        .find_map(|normalized| common_password_entry(&normalized))
+}
+
+// "1" is ambiguous (i or l), so the caller picks which reading to use
+fn leet_normalize(password: &str, one_as: char) -> String {
+    password
+        .chars()
+        .map(|c| match c {
+            '@' | '4' => 'a',
+            '3' => 'e',
+            '1' => one_as,
+            '!' | '|' => 'i',
+            '0' => 'o',
+            '$' | '5' => 's',
+            '7' | '+' => 't',
+            other => other,
+        })
+        .collect()
+}
+
+fn count_repeated_chars(password: &str) -> u32 {
+    let chars: Vec<char> = password.chars().collect();
+    let mut count = 0;
+    for window in chars.windows(3) {
+        if window[0] == window[1] && window[1] == window[2] {
+            count += 1;
+        }
+    }
+    count
+}
+
+// The part of the password pattern checks look at, and its char offset. Mandated fixed
+// prefixes/suffixes would otherwise trigger the same sequence warnings for every user
+fn pattern_region<'a>(password: &'a str, policy: &PasswordPolicy) -> (&'a str, usize) {
+    let char_count = password.chars().count();
+    let start = (policy.ignore_first_chars.unwrap_or(0) as usize).min(char_count);
+    let end = char_count.saturating_sub(policy.ignore_last_chars.unwrap_or(0) as usize).max(start);
+
+    let byte_index = |n: usize| password.char_indices().nth(n).map_or(password.len(), |(i, _)| i);
+    (&password[byte_index(start)..byte_index(end)], start)
+}
+
+// zxcvbn's guess estimate at the offline fast-hash rate, a blank password cracks instantly
+fn estimate_offline_crack_seconds(password: &str) -> f64 {
+    zxcvbn::zxcvbn(password, &[])
+        .map(|estimate| estimate.guesses() as f64 / OFFLINE_FAST_HASH_GUESSES_PER_SECOND)
+        .unwrap_or(0.0)
+}
+
+fn describe_duration(seconds: f64, language: FeedbackLanguage) -> String {
+    const UNITS: &[(&str, &str, f64)] = &[
+        ("years", "años", 365.0 * 86_400.0),
+        ("days", "días", 86_400.0),
+        ("hours", "horas", 3_600.0),
+        ("minutes", "minutos", 60.0),
+        ("seconds", "segundos", 1.0),
+    ];
+    match UNITS.iter().find(|(_, _, unit_seconds)| seconds >= *unit_seconds) {
+        Some((english, spanish, unit_seconds)) => {
+            let count = (seconds / unit_seconds).floor();
+            match language {
+                FeedbackLanguage::English => format!("about {:.0} {}", count, english),
+                FeedbackLanguage::Spanish => format!("unos {:.0} {}", count, spanish),
+            }
+        }
+        None => match language {
+            FeedbackLanguage::English => "less than a second".to_string(),
+            FeedbackLanguage::Spanish => "menos de un segundo".to_string(),
+        },
+    }
+}
+
+// "pass!word" counts, "password!" and "!password" don't
+fn has_interior_symbol(password: &str) -> bool {
+    let chars: Vec<char> = password.chars().collect();
+    chars.len() > 2 && chars[1..chars.len() - 1].iter().any(|c| !c.is_alphanumeric())
+}
+
+// "Password1!" yes, "pAsswOrd1!" and "PASSWORD" no
+fn is_only_first_capitalized(password: &str) -> bool {
+    let mut chars = password.chars();
+    chars.next().is_some_and(|c| c.is_uppercase())
+        && chars.clone().any(|c| c.is_lowercase())
+        && !chars.any(|c| c.is_uppercase())
+}
+
+// At least 4 letters, each differing in case from the previous letter ("pAsSwOrD", "PaSs1WoRd").
+// Non-letters are skipped so a digit in the middle doesn't hide the pattern
+fn is_studly_caps(password: &str) -> bool {
+    let cases: Vec<bool> = password.chars().filter(|c| c.is_alphabetic()).map(|c| c.is_uppercase()).collect();
+    cases.len() >= 4 && cases.windows(2).all(|pair| pair[0] != pair[1])
+}
+
+// Start (char index) and length of the longest run of same-class characters,
+// "12345" is one run of 5 even though every digit differs
+fn longest_class_run(password: &str) -> (usize, u32) {
+    let mut longest = (0, 0);
+    let mut run_start = 0;
+    let mut previous_class = None;
+
+    for (i, c) in password.chars().enumerate() {
+        let class = char_class(c);
+        if previous_class != Some(class) {
+            run_start = i;
+            previous_class = Some(class);
+        }
+        let run_len = (i - run_start + 1) as u32;
+        if run_len > longest.1 {
+            longest = (run_start, run_len);
+        }
+    }
+    longest
+}
+
+fn char_class(c: char) -> u8 {
+    if c.is_uppercase() { 0 }
+    else if c.is_lowercase() { 1 }
+    else if c.is_numeric() { 2 }
+    else { 3 }
+}
+
+// Reversals and rotations of an identity term, which substring checks don't catch.
+// Terms under 3 characters are skipped, they'd match too much by accident
+fn is_transformed_identity(password: &str, identity_terms: &[String]) -> bool {
+    let lower_password: Vec<char> = password.trim().to_lowercase().chars().collect();
+
+    identity_terms.iter().any(|term| {
+        let term: Vec<char> = term.trim().to_lowercase().chars().collect();
+        if term.len() < 3 || term.len() != lower_password.len() {
+            return false;
+        }
+
+        let reversed: Vec<char> = term.iter().rev().copied().collect();
+        if reversed == lower_password && reversed != term {
+            return true;
+        }
+        (1..term.len()).any(|shift| {
+            let mut rotated = term.clone();
+            rotated.rotate_left(shift);
+            rotated == lower_password && rotated != term
+        })
+    })
+}
+
+fn count_sequential_chars(password: &str, skip_digit_runs: bool) -> u32 {
+    let lower_password = password.to_lowercase();
+    sequence_patterns(skip_digit_runs).filter(|re| re.is_match(&lower_password)).count() as u32
+}
+
+fn sequence_patterns(skip_digit_runs: bool) -> impl Iterator<Item = &'static Regex> {
+    COMMON_PATTERNS_RE.iter().filter(move |re| !(skip_digit_runs && re.as_str() == DIGIT_RUN_PATTERN))
+}
+
+// Row, column and whether shift is needed, for a US QWERTY key
+fn keyboard_position(c: char) -> Option<(usize, usize, bool)> {
+    KEYBOARD_ROWS.iter().enumerate().find_map(|(row, (plain, shifted))| {
+        plain.chars().position(|key| key == c).map(|col| (row, col, false))
+            .or_else(|| shifted.chars().position(|key| key == c).map(|col| (row, col, true)))
+    })
+}
+
+// Rows are staggered to the right, so a key touches the row above at the same and next
+// column and the row below at the same and previous column ("1qaz" is a straight line)
+fn keys_adjacent((row_a, col_a): (usize, usize), (row_b, col_b): (usize, usize)) -> bool {
+    match row_b as isize - row_a as isize {
+        0 => col_a.abs_diff(col_b) == 1,
+        -1 => col_b == col_a || col_b == col_a + 1,
+        1 => col_b == col_a || col_b + 1 == col_a,
+        _ => false,
+    }
+}
+
+// A straight run of 4+ keys, each adjacent to the last in the same direction (a row run like
+// "QWER" or a column like "!QAZ"), with a shifted key past the first one so a word's leading
+// capital doesn't count. Plain walks like "qwer" are left to the sequence patterns
+fn has_shift_keyboard_walk(password: &str) -> bool {
+    let keys: Vec<Option<(usize, usize, bool)>> = password.chars().map(keyboard_position).collect();
+    let step = |(row_a, col_a, _): (usize, usize, bool), (row_b, col_b, _): (usize, usize, bool)| {
+        (row_b as isize - row_a as isize, col_b as isize - col_a as isize)
+    };
+
+    (0..keys.len()).any(|start| {
+        let mut direction = None;
+        let mut end = start + 1;
+        while let (Some(Some(prev)), Some(Some(key))) = (keys.get(end - 1), keys.get(end)) {
+            let this_step = step(*prev, *key);
+            if !keys_adjacent((prev.0, prev.1), (key.0, key.1)) || direction.is_some_and(|d| d != this_step) {
+                break;
+            }
+            direction = Some(this_step);
+            end += 1;
+        }
+        end - start >= MIN_KEYBOARD_WALK_LEN && keys[start + 1..end].iter().flatten().any(|&(_, _, shifted)| shifted)
+    })
+}
+
+// Length of a 2-4 char unit repeated at least 3 times (a trailing partial unit is fine) whose
+// neighbouring characters all differ in class, e.g. 2 for "a1a1a1a1"
+fn alternating_unit_len(password: &str) -> Option<usize> {
+    let chars: Vec<char> = password.chars().collect();
+
+    (2..=4).find(|&unit_len| {
+        let unit = &chars[..unit_len.min(chars.len())];
+        chars.len() >= unit_len * 3
+            && chars.iter().enumerate().all(|(i, c)| *c == unit[i % unit_len])
+            && (0..unit_len).all(|i| char_class(unit[i]) != char_class(unit[(i + 1) % unit_len]))
+    })
+}
+
+// Length of the longest run (at least MIN_INTERLEAVED_LEN) alternating letter and digit where
+// the letters and the digits each step by the same +1 or -1, e.g. 6 for "a1b2c3" or "1a2b3c"
+fn interleaved_sequence_len(password: &str) -> Option<usize> {
+    let chars: Vec<char> = password.to_lowercase().chars().collect();
+    let is_pair = |a: char, b: char| {
+        (a.is_ascii_lowercase() && b.is_ascii_digit()) || (a.is_ascii_digit() && b.is_ascii_lowercase())
+    };
+
+    let mut longest = 0;
+    for start in 0..chars.len().saturating_sub(1) {
+        if !is_pair(chars[start], chars[start + 1]) {
+            continue;
+        }
+        let mut step = None;
+        let mut end = start + 2;
+        while end < chars.len() && is_pair(chars[end - 1], chars[end]) {
+            let diff = chars[end] as i32 - chars[end - 2] as i32;
+            if diff.abs() != 1 || step.is_some_and(|step| step != diff) {
+                break;
+            }
+            step = Some(diff);
+            end += 1;
+        }
+        longest = longest.max(end - start);
+    }
+    (longest >= MIN_INTERLEAVED_LEN).then_some(longest)
+}
+
+fn find_banned_sequence(password: &str, banned_sequences: &[String]) -> Option<String> {
+    let chars: Vec<char> = password.to_lowercase().chars().collect();
+    banned_sequences
+        .iter()
+        .find(|sequence| {
+            let pattern: Vec<char> = sequence.to_lowercase().chars().collect();
+            !pattern.is_empty()
+                && chars.windows(pattern.len()).any(|window| {
+                    window.iter().zip(&pattern).all(|(c, p)| match p {
+                        '#' => c.is_ascii_digit(),
+                        '?' => true,
+                        literal => c == literal,
+                    })
+                })
+        })
+        .cloned()
+}
+
+fn find_forbidden_substring(password: &str) -> Option<String> {
+    let guard = FORBIDDEN_SUBSTRINGS.read().ok()?;
+    let forbidden = guard.as_ref()?;
+    forbidden
+        .automaton
+        .find(&password.to_lowercase())
+        .map(|m| forbidden.terms[m.pattern().as_usize()].clone())
+}
+
+fn is_numeric_only(password: &str) -> bool {
+    !password.is_empty() && password.chars().all(|c| c.is_ascii_digit())
+}
+
+// Order-preserving parallel map. A chunk_size of 0 (or none) leaves work splitting to rayon.
+// Bigger chunks cut scheduling overhead on uniform batches but let one slow chunk hold up the
+// end of the batch; smaller chunks balance batches that mix tiny and huge passwords
+fn par_map_chunked<T, R, F>(items: &[T], chunk_size: Option<u32>, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    match chunk_size.filter(|&size| size > 0) {
+        Some(size) => items.par_chunks(size as usize).flat_map_iter(|chunk| chunk.iter().map(&f)).collect(),
+        None => items.par_iter().map(f).collect(),
+    }
+}
+
+fn parse_hash_scheme(algorithm: &str, params_json: &str) -> Result<HashScheme> {
+    let params_json = if params_json.trim().is_empty() { "{}" } else { params_json };
+    let invalid_params = |e: serde_json::Error| Error::from_reason(format!("Invalid params JSON: {}", e));
+
+    match algorithm.to_lowercase().as_str() {
+        "argon2" | "argon2id" => {
+            let params: Argon2Params = serde_json::from_str(params_json).map_err(invalid_params)?;
+            Ok(HashScheme::Argon2(params.to_argon2()?))
+        }
+        "bcrypt" => {
+            let params: BcryptParams = serde_json::from_str(params_json).map_err(invalid_params)?;
+            Ok(HashScheme::Bcrypt(params.cost))
+        }
+        other => Err(Error::from_reason(format!("Unsupported algorithm: {}", other))),
+    }
+}
+
+impl Argon2Params {
+    fn to_argon2(self) -> Result<Params> {
+        Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| Error::from_reason(format!("Invalid Argon2 params: {}", e)))
+    }
+}
+
+// Algorithm family of a stored hash, named as parse_hash_scheme accepts it
+fn hash_algorithm(hash: &str) -> Option<&'static str> {
+    if hash.starts_with("$argon2") {
+        Some("argon2")
+    } else if ["$2a$", "$2b$", "$2x$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix)) {
+        Some("bcrypt")
+    } else {
+        None
+    }
+}
+
+impl HashScheme {
+    fn algorithm(&self) -> &'static str {
+        match self {
+            HashScheme::Argon2(_) => "argon2",
+            HashScheme::Bcrypt(_) => "bcrypt",
+        }
+    }
+
+    fn hash(&self, password: &[u8]) -> Result<String> {
+        match self {
+            HashScheme::Argon2(params) => {
+                let salt = SaltString::generate(&mut OsRng);
+                Argon2::new(Algorithm::default(), Version::default(), params.clone())
+                    .hash_password(password, &salt)
+                    .map(|hash| hash.to_string())
+                    .map_err(|_| Error::from_reason("Failed to hash password"))
+            }
+            HashScheme::Bcrypt(cost) => {
+                bcrypt::hash(password, *cost).map_err(|_| Error::from_reason("Failed to hash password"))
+            }
+        }
+    }
+}
+
+// RFC 2104 HMAC over SHA-256
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+fn peppered_argon2(pepper: &[u8]) -> Result<Argon2<'_>> {
+    Argon2::new_with_secret(pepper, Algorithm::default(), Version::default(), Params::default())
+        .map_err(|_| Error::from_reason("Invalid pepper"))
+}
+
+// (code, message) of the highest priority weakness, see primary_weakness_message for the order
+fn primary_weakness(analysis: &PasswordAnalysis, breach_count: Option<u32>) -> Option<(&'static str, String)> {
+    let item_message = |code: &str| {
+        analysis.feedback_items.iter().find(|item| item.code == code).map(|item| item.message.clone())
+    };
+    let patterns = &analysis.pattern_analysis;
+
+    if let Some(count) = breach_count.filter(|&count| count > 0) {
+        return Some(("BREACHED", format!("This password has appeared in {} known data breaches.", count)));
+    }
+    for code in ["COMMON_PASSWORD", "LEET_COMMON", "COMMON_PHRASE", "TOO_SHORT"] {
+        if let Some(message) = item_message(code) {
+            return Some((code, message));
+        }
+    }
+    // One distinct character, and enough of it to repeat (doesn't need `length`, which may be bucketed)
+    if patterns.repeated_chars > 0 && analysis.distribution_entropy_bits == 0.0 {
+        return Some(("SINGLE_CHAR", "Password is a single character repeated.".to_string()));
+    }
+    if let Some(message) = item_message("SEQUENTIAL_CHARS") {
+        return Some(("SEQUENTIAL_CHARS", message));
+    }
+    if analysis.effective_entropy_bits < LOW_ENTROPY_BITS {
+        return Some(("LOW_ENTROPY", "Password is too predictable, make it longer or more varied.".to_string()));
+    }
+    for code in ["MISSING_SYMBOL", "MISSING_NUMBER", "MISSING_UPPERCASE"] {
+        if let Some(message) = item_message(code) {
+            return Some((code, message));
+        }
+    }
+    None
+}
+
+// Unknown breach years are treated as current, better to over- than under-weight them
+fn breach_recency_weight(most_recent_year: Option<u32>) -> f64 {
+    match most_recent_year {
+        Some(year) => {
+            let years_since = current_year().saturating_sub(year) as f64;
+            0.5f64.powf(years_since / BREACH_HALF_LIFE_YEARS)
+        }
+        None => 1.0,
+    }
+}
+
+// UTC calendar year from the system clock (days-to-civil conversion, no date crate needed)
+fn current_year() -> u32 {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let year = year_of_era + era * 400 + if month_index >= 10 { 1 } else { 0 };
+    year as u32
+}
+
+// Picks the cheapest edit that moves a non-compliant password towards compliance
+fn next_edit_step(password: &str, analysis: &PasswordAnalysis, policy: &PasswordPolicy) -> EditStep {
+    let (pattern_region, region_offset) = pattern_region(password, policy);
+    if let Some(position) = first_sequence_break(pattern_region, policy.exempts_numeric(password)) {
+        let position = position + region_offset as u32;
+        return EditStep { kind: EditKind::BreakSequence, count: None, position: Some(position) };
+    }
+    if policy.exempts_numeric(password) && analysis.pattern_analysis.repeated_chars > 0 {
+        let chars: Vec<char> = pattern_region.chars().collect();
+        if let Some(start) = chars.windows(3).position(|w| w[0] == w[1] && w[1] == w[2]) {
+            let position = (region_offset + start + 2) as u32;
+            return EditStep { kind: EditKind::BreakSequence, count: None, position: Some(position) };
+        }
+    }
+    if let Some(max_run) = policy.max_class_run {
+        let (run_start, run_len) = longest_class_run(password);
+        if run_len > max_run {
+            let position = (run_start + max_run.max(1) as usize) as u32;
+            return EditStep { kind: EditKind::BreakSequence, count: None, position: Some(position) };
+        }
+    }
+
+    if policy.require_interior_symbol.unwrap_or(false) && !has_interior_symbol(password) && password.chars().count() >= 2 {
+        return EditStep { kind: EditKind::InsertSymbol, count: None, position: Some(1) };
+    }
+
+    let patterns = &analysis.pattern_analysis;
+    if analysis.strength_score < policy.min_strength_score() {
+        // Biggest score bonus first
+        let missing_class = [
+            (!patterns.has_symbols, EditKind::AddSymbol),
+            (!patterns.has_numbers, EditKind::AddNumber),
+            (!patterns.has_uppercase, EditKind::AddUppercase),
+            (!patterns.has_lowercase, EditKind::AddLowercase),
+        ]
+        .into_iter()
+        .find_map(|(missing, kind)| missing.then_some(kind));
+
+        if let Some(kind) = missing_class {
+            return EditStep { kind, count: None, position: None };
+        }
+    }
+
+    let shortfall = (policy.min_length() as usize).saturating_sub(password.len()).max(1);
+    EditStep { kind: EditKind::AppendChars, count: Some(shortfall as u32), position: None }
+}
+
+fn apply_edit_step(password: &mut String, step: &EditStep) {
+    match step.kind {
+        EditKind::AppendChars => {
+            // Alternating filler never forms a repeat or a sequence on its own
+            let filler = ['x', 'q'];
+            let start = password.chars().count();
+            for i in 0..step.count.unwrap_or(0) as usize {
+                password.push(filler[(start + i) % filler.len()]);
+            }
+        }
+        EditKind::AddUppercase => password.push('Q'),
+        EditKind::AddLowercase => password.push('x'),
+        EditKind::AddNumber => password.push('7'),
+        EditKind::AddSymbol => password.push('!'),
+        EditKind::BreakSequence | EditKind::InsertSymbol => {
+            let symbol = if step.kind == EditKind::BreakSequence { '-' } else { '!' };
+            let position = step.position.unwrap_or(0) as usize;
+            let byte_index = password.char_indices().nth(position).map_or(password.len(), |(i, _)| i);
+            password.insert(byte_index, symbol);
+        }
+    }
+}
+
+// Char index that splits the first sequential pattern so it no longer matches
+fn first_sequence_break(password: &str, skip_digit_runs: bool) -> Option<u32> {
+    let lower_password = password.to_lowercase();
+    sequence_patterns(skip_digit_runs)
+        .filter_map(|re| re.find(&lower_password))
+        .min_by_key(|m| m.start())
+        .map(|m| (lower_password[..m.start()].chars().count() + 2) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_per_hash_splits_budget_across_concurrent_hashes() {
+        assert_eq!(memory_per_hash_for_budget(1024, 4, 1.0), 262_144);
+        assert_eq!(memory_per_hash_for_budget(2048, 16, 1.0), 131_072);
+        assert_eq!(memory_per_hash_for_budget(1024, 4, 2.0), 131_072);
+        assert_eq!(memory_per_hash_for_budget(1000, 3, 1.5), 227_555);
+    }
+
+    #[test]
+    fn memory_per_hash_treats_degenerate_inputs_as_one() {
+        assert_eq!(memory_per_hash_for_budget(512, 0, 1.0), 524_288);
+        assert_eq!(memory_per_hash_for_budget(1024, 4, 0.5), 262_144);
+        assert_eq!(memory_per_hash_for_budget(1024, 4, f64::NAN), 262_144);
+    }
+
+    #[test]
+    fn memory_per_hash_stays_within_argon2_limits() {
+        assert_eq!(memory_per_hash_for_budget(1, 1000, 1.0), Params::MIN_M_COST);
+        assert_eq!(memory_per_hash_for_budget(0, 1, 1.0), Params::MIN_M_COST);
+        assert_eq!(memory_per_hash_for_budget(u32::MAX, 1, 1.0), Params::MAX_M_COST);
+    }
+
+    fn has_code(analysis: &PasswordAnalysis, code: &str) -> bool {
+        analysis.feedback_items.iter().any(|item| item.code == code)
+    }
+
+    #[test]
+    fn whitespace_padded_common_passwords_are_flagged() {
+        for password in ["    admin", "password    "] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert!(has_code(&analysis, "COMMON_PASSWORD"), "{:?} not flagged", password);
+            assert!(!analysis.is_compliant);
+        }
+    }
+
+    #[test]
+    fn whitespace_padding_still_counts_in_reported_length() {
+        let analysis = analyze_password("    admin", &PasswordPolicy::default(), &[]);
+        assert_eq!(analysis.pattern_analysis.length, 9);
+    }
+
+    fn apply_plan(password: &str, plan: &[EditStep]) -> String {
+        let mut edited = password.to_string();
+        for step in plan {
+            apply_edit_step(&mut edited, step);
+        }
+        edited
+    }
+
+    #[test]
+    fn applying_compliance_plan_makes_password_compliant() {
+        let policy = PasswordPolicy { max_class_run: Some(4), require_interior_symbol: Some(true), ..Default::default() };
+        for password in ["abc", "hello", "sunshine", "xyzabcdef", "Zebra99999999", "k"] {
+            let plan = compliance_plan(password.to_string(), policy.clone());
+            assert!(!plan.is_empty(), "{:?} needs edits", password);
+            let edited = apply_plan(password, &plan);
+            assert!(analyze_password(&edited, &policy, &[]).is_compliant, "{:?} -> {:?} not compliant", password, edited);
+        }
+    }
+
+    #[test]
+    fn compliance_plan_is_empty_for_compliant_password() {
+        assert!(compliance_plan("Gx7!mRq2#vLp".to_string(), PasswordPolicy::default()).is_empty());
+    }
+
+    #[test]
+    fn compliance_plan_breaks_sequences_in_place() {
+        let plan = compliance_plan("Qx!abcQx!9".to_string(), PasswordPolicy::default());
+        assert_eq!(plan[0].kind, EditKind::BreakSequence);
+        assert_eq!(plan[0].position, Some(5));
+    }
+
+    #[test]
+    fn long_class_run_respects_threshold() {
+        let run_check = |max_class_run| {
+            let policy = PasswordPolicy { max_class_run, ..Default::default() };
+            has_code(&analyze_password("abcde12345", &policy, &[]), "LONG_CLASS_RUN")
+        };
+        assert_eq!(analyze_password("abcde12345", &PasswordPolicy::default(), &[]).pattern_analysis.longest_class_run, 5);
+        assert!(run_check(Some(3)));
+        assert!(run_check(Some(4)));
+        assert!(!run_check(Some(5)));
+        assert!(!run_check(Some(8)));
+        assert!(!run_check(None));
+    }
+
+    #[test]
+    fn long_class_run_fails_compliance() {
+        let policy = PasswordPolicy { max_class_run: Some(4), ..Default::default() };
+        let analysis = analyze_password("Qx!9abcde12345", &policy, &[]);
+        let item = analysis.feedback_items.iter().find(|item| item.code == "LONG_CLASS_RUN").unwrap();
+        assert_eq!(item.severity, Severity::Critical);
+        assert!(!analysis.is_compliant);
+    }
+
+    #[test]
+    fn pepper_rotation_reports_position_of_matching_pepper() {
+        let pepper = b"current-pepper".to_vec();
+        let hash = hash_password_with_pepper("hunter2hunter2".to_string(), pepper.clone()).unwrap();
+        let others = [b"old-pepper-1".to_vec(), b"old-pepper-2".to_vec()];
+
+        for position in 0..=others.len() {
+            let mut peppers = others.to_vec();
+            peppers.insert(position, pepper.clone());
+            let result = verify_with_pepper_rotation("hunter2hunter2".to_string(), hash.clone(), peppers).unwrap();
+            assert!(result.valid);
+            assert_eq!(result.pepper_index, Some(position as u32));
+        }
+    }
+
+    #[test]
+    fn pepper_rotation_fails_without_matching_pepper() {
+        let hash = hash_password_with_pepper("hunter2hunter2".to_string(), b"current-pepper".to_vec()).unwrap();
+        let result = verify_with_pepper_rotation("hunter2hunter2".to_string(), hash.clone(), vec![b"old-pepper-1".to_vec()]).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.pepper_index, None);
+
+        let result = verify_with_pepper_rotation("wrong".to_string(), hash, vec![b"current-pepper".to_vec()]).unwrap();
+        assert!(!result.valid);
+
+        let result = verify_with_pepper_rotation("hunter2hunter2".to_string(), "not a hash".to_string(), vec![b"current-pepper".to_vec()]).unwrap();
+        assert!(!result.valid);
+    }
+
+    #[test]
+    fn reversed_and_rotated_identity_terms_are_flagged() {
+        let terms = ["jsmith".to_string()];
+        for password in ["htimsj", "smithj", "hjsmit", "HTIMSJ"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &terms);
+            assert!(analysis.pattern_analysis.identity_transformed, "{:?} not flagged", password);
+            assert!(has_code(&analysis, "IDENTITY_TRANSFORMED"));
+        }
+    }
+
+    #[test]
+    fn unrelated_or_identical_passwords_are_not_identity_transformed() {
+        let terms = ["jsmith".to_string()];
+        for password in ["jsmith", "jsmiht", "Gx7!mRq2#vLp"] {
+            assert!(!analyze_password(password, &PasswordPolicy::default(), &terms).pattern_analysis.identity_transformed, "{:?}", password);
+        }
+        assert!(!is_transformed_identity("ja", &["aj".to_string()]));
+    }
+
+    #[test]
+    fn interior_symbol_required_when_policy_asks() {
+        let policy = PasswordPolicy { require_interior_symbol: Some(true), ..Default::default() };
+        assert!(has_code(&analyze_password("password!", &policy, &[]), "EXTERIOR_SYMBOL_ONLY"));
+        assert!(has_code(&analyze_password("!password", &policy, &[]), "EXTERIOR_SYMBOL_ONLY"));
+        assert!(!has_code(&analyze_password("pass!word", &policy, &[]), "EXTERIOR_SYMBOL_ONLY"));
+        assert!(!has_code(&analyze_password("password!", &PasswordPolicy::default(), &[]), "EXTERIOR_SYMBOL_ONLY"));
+    }
+
+    #[test]
+    fn stable_check_is_byte_identical_across_runs() {
+        let run = || {
+            let analysis = check_password_policy_stable("Tr0ub4dor&3".to_string(), None, Some(vec!["troubador".to_string()])).unwrap();
+            assert_eq!(analysis.analysis_time_ms, 0);
+            serde_json::to_vec(&analysis).unwrap()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn primary_weakness_follows_documented_priority() {
+        let code = |password: &str, breach_count: Option<u32>| {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            primary_weakness(&analysis, breach_count).map(|(code, _)| code)
+        };
+        assert_eq!(code("password", Some(5)), Some("BREACHED"));
+        assert_eq!(code("admin", None), Some("COMMON_PASSWORD"));
+        assert_eq!(code("aaaa", None), Some("TOO_SHORT"));
+        assert_eq!(code("aaaaaaaaaaaa", None), Some("SINGLE_CHAR"));
+        assert_eq!(code("abcdefghij", None), Some("SEQUENTIAL_CHARS"));
+        assert_eq!(code("zqxwvmkp", None), Some("LOW_ENTROPY"));
+        assert_eq!(code("Gx7mRq2vLpKz", None), Some("MISSING_SYMBOL"));
+        assert_eq!(code("Gx7!mRq2#vLp", None), None);
+        assert_eq!(code("Gx7!mRq2#vLp", Some(0)), None);
+    }
+
+    #[test]
+    fn primary_weakness_message_uses_the_feedback_wording() {
+        let analysis = analyze_password("admin", &PasswordPolicy::default(), &[]);
+        assert_eq!(primary_weakness_message(analysis, None).as_deref(), Some("This password is too common and easy to guess."));
+        let analysis = analyze_password("admin", &PasswordPolicy::default(), &[]);
+        assert!(primary_weakness_message(analysis, Some(42)).unwrap().contains("42"));
+    }
+
+    fn risk_for(password: &str, breach: Option<BreachInfo>, breach_weight: Option<f64>) -> RiskAssessment {
+        let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+        assess_risk(analysis, breach, Some(RiskConfig { breach_weight }))
+    }
+
+    #[test]
+    fn recent_breach_weighs_more_than_old_breach_at_equal_count() {
+        let year = current_year();
+        let recent = risk_for("Gx7!mRq2#vLp", Some(BreachInfo { count: 1000, most_recent_year: Some(year) }), None);
+        let old = risk_for("Gx7!mRq2#vLp", Some(BreachInfo { count: 1000, most_recent_year: Some(year - 12) }), None);
+        let clean = risk_for("Gx7!mRq2#vLp", None, None);
+
+        assert_eq!(recent.breach_recency_weight, 1.0);
+        assert_eq!(old.breach_recency_weight, 0.125);
+        assert_eq!(clean.breach_recency_weight, 0.0);
+        assert!(recent.risk_score > old.risk_score);
+        assert!(old.risk_score > clean.risk_score);
+    }
+
+    #[test]
+    fn breach_recency_weight_halves_every_four_years() {
+        let year = current_year();
+        assert_eq!(breach_recency_weight(Some(year - 4)), 0.5);
+        assert_eq!(breach_recency_weight(Some(year - 8)), 0.25);
+        assert_eq!(breach_recency_weight(Some(year + 1)), 1.0);
+        assert_eq!(breach_recency_weight(None), 1.0);
+    }
+
+    #[test]
+    fn paste_artifacts_are_stripped_and_flagged() {
+        let analysis = analyze_password("\u{FEFF}Gx7mRq\u{200B}2vLp\n", &PasswordPolicy::default(), &[]);
+        assert!(analysis.pattern_analysis.had_paste_artifacts);
+        assert_eq!(analysis.pattern_analysis.length, 10);
+        assert!(!analysis.pattern_analysis.has_symbols);
+        assert_eq!(analysis.strength_score, analyze_password("Gx7mRq2vLp", &PasswordPolicy::default(), &[]).strength_score);
+    }
+
+    #[test]
+    fn clean_passwords_have_no_paste_artifacts() {
+        let analysis = analyze_password("Gx7mRq2vLp", &PasswordPolicy::default(), &[]);
+        assert!(!analysis.pattern_analysis.had_paste_artifacts);
+        assert_eq!(strip_paste_artifacts("👨\u{200D}👩"), "👨\u{200D}👩");
+    }
+
+    const FAST_ARGON2_JSON: &str = r#"{"memoryKib": 1024, "iterations": 1, "parallelism": 1}"#;
+
+    fn verified(passwords: &[&str]) -> Vec<VerifiedPassword> {
+        passwords.iter().map(|password| VerifiedPassword { password: password.to_string() }).collect()
+    }
+
+    #[test]
+    fn batch_rehash_produces_argon2_hashes_in_input_order() {
+        let passwords = ["alpha-pass", "bravo-pass", "charlie-pass"];
+        let hashes = batch_rehash(verified(&passwords), "argon2".to_string(), FAST_ARGON2_JSON.to_string()).unwrap();
+
+        assert_eq!(hashes.len(), passwords.len());
+        for (password, hash) in passwords.iter().zip(&hashes) {
+            assert!(hash.starts_with("$argon2id$v=19$m=1024,t=1,p=1$"));
+            assert!(verify_password_hash(password.to_string(), hash.clone()).unwrap());
+        }
+        assert!(!verify_password_hash(passwords[1].to_string(), hashes[0].clone()).unwrap());
+    }
+
+    #[test]
+    fn batch_rehash_supports_bcrypt_and_rejects_bad_input() {
+        let hashes = batch_rehash(verified(&["alpha-pass"]), "bcrypt".to_string(), r#"{"cost": 4}"#.to_string()).unwrap();
+        assert!(hashes[0].starts_with("$2b$04$"));
+        assert!(bcrypt::verify("alpha-pass", &hashes[0]).unwrap());
+
+        assert!(batch_rehash(verified(&["alpha-pass"]), "md5".to_string(), String::new()).is_err());
+        assert!(batch_rehash(verified(&["alpha-pass"]), "argon2".to_string(), "{".to_string()).is_err());
+    }
+
+    #[test]
+    fn long_random_numeric_passes_only_with_allow_numeric_only() {
+        let allowed = PasswordPolicy { allow_numeric_only: Some(true), ..Default::default() };
+        assert!(analyze_password("830572916483", &allowed, &[]).is_compliant);
+
+        let analysis = analyze_password("830572916483", &PasswordPolicy::default(), &[]);
+        assert!(!analysis.is_compliant);
+        assert!(has_code(&analysis, "NUMERIC_ONLY"));
+    }
+
+    #[test]
+    fn allow_numeric_only_still_catches_numeric_patterns() {
+        let allowed = PasswordPolicy { allow_numeric_only: Some(true), ..Default::default() };
+        assert!(has_code(&analyze_password("830123916483", &allowed, &[]), "SEQUENTIAL_CHARS"));
+        assert!(has_code(&analyze_password("830577716483", &allowed, &[]), "REPEATED_CHARS"));
+        assert!(!analyze_password("8305", &allowed, &[]).is_compliant);
+        assert!(has_code(&analyze_password("zqxwvmkp", &allowed, &[]), "LOW_STRENGTH"));
+    }
+
+    #[test]
+    fn numeric_exemption_drops_only_the_digit_run_pattern() {
+        let kept: Vec<&str> = sequence_patterns(true).map(Regex::as_str).collect();
+        assert_eq!(kept.len(), COMMON_PATTERNS_RE.len() - 1);
+        assert!(!kept.contains(&DIGIT_RUN_PATTERN));
+        assert_eq!(sequence_patterns(false).count(), COMMON_PATTERNS_RE.len());
+    }
+
+    #[test]
+    fn hmac_sha256_matches_rfc_4231_vectors() {
+        let key_4: Vec<u8> = (0x01..=0x19).collect();
+        let cases: [(&[u8], &[u8], &str); 6] = [
+            (&[0x0b; 20], b"Hi There", "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"),
+            (b"Jefe", b"what do ya want for nothing?", "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"),
+            (&[0xaa; 20], &[0xdd; 50], "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe"),
+            (&key_4, &[0xcd; 50], "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b"),
+            (&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"),
+            (&[0xaa; 131], b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.",
+                "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2"),
+        ];
+        for (key, message, expected) in cases {
+            assert_eq!(hex::encode(hmac_sha256(key, message)), expected);
+        }
+    }
+
+    #[test]
+    fn reuse_fingerprint_is_deterministic_per_salt() {
+        let fingerprint = |password: &str, salt: &[u8]| reuse_fingerprint(password.to_string(), salt.to_vec()).unwrap();
+        assert_eq!(fingerprint("hunter2", b"shared-salt"), fingerprint("hunter2", b"shared-salt"));
+        assert_eq!(fingerprint("hunter2", b"shared-salt"), hex::encode(hmac_sha256(b"shared-salt", b"hunter2")));
+        assert_ne!(fingerprint("hunter2", b"shared-salt"), fingerprint("hunter3", b"shared-salt"));
+        assert_ne!(fingerprint("hunter2", b"shared-salt"), fingerprint("hunter2", b"other-salt"));
+        assert!(reuse_fingerprint("hunter2".to_string(), Vec::new()).is_err());
+    }
+
+    #[test]
+    fn forbid_current_year_rejects_only_the_current_year() {
+        let policy = PasswordPolicy { forbid_current_year: Some(true), ..Default::default() };
+        let year = current_year();
+        assert!(has_code(&analyze_password(&format!("Gx!mRq{}vLp", year), &policy, &[]), "CONTAINS_CURRENT_YEAR"));
+        assert!(!has_code(&analyze_password(&format!("Gx!mRq{}vLp", year - 1), &policy, &[]), "CONTAINS_CURRENT_YEAR"));
+        assert!(!has_code(&analyze_password(&format!("Gx!mRq{}vLp", year + 1), &policy, &[]), "CONTAINS_CURRENT_YEAR"));
+        assert!(!has_code(&analyze_password(&format!("Gx!mRq{}vLp", year), &PasswordPolicy::default(), &[]), "CONTAINS_CURRENT_YEAR"));
+    }
+
+    #[test]
+    fn current_year_matches_the_system_clock() {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let approximate_year = 1970 + seconds / 31_556_952;
+        assert!(current_year().abs_diff(approximate_year as u32) <= 1);
+    }
+
+    #[test]
+    fn exported_scoring_model_round_trips_to_the_same_score() {
+        let model_json = serde_json::to_string(&scoring_model()).unwrap();
+        for password in ["password", "Gx7!mRq2#vLp", "abc123", "pAsSwOrD", "!QAZ2wsx"] {
+            let expected = analyze_password(password, &PasswordPolicy::default(), &[]).strength_score;
+            assert_eq!(score_with_model_json(password.to_string(), model_json.clone()).unwrap(), expected, "{:?}", password);
+        }
+    }
+
+    #[test]
+    fn score_with_model_json_rejects_invalid_json_and_missing_fields() {
+        let error = score_with_model_json("password".to_string(), "{not json".to_string()).unwrap_err();
+        assert!(error.reason.contains("Invalid scoring model JSON"));
+
+        let error = score_with_model_json("Gx7!mRq2#vLp".to_string(), r#"{"maxScore": 10}"#.to_string()).unwrap_err();
+        assert!(error.reason.contains("missing field"), "{}", error.reason);
+
+        let mut model = serde_json::to_value(scoring_model()).unwrap();
+        model.as_object_mut().unwrap().remove("sequencePenalty");
+        assert!(score_with_model_json("password".to_string(), model.to_string()).is_err());
+    }
+
+    #[test]
+    fn score_with_model_json_rejects_another_version() {
+        let model = ScoringModel { version: SCORING_MODEL_VERSION + 1, max_score: 10, ..scoring_model() };
+        let error = score_with_model_json("Gx7!mRq2#vLp".to_string(), serde_json::to_string(&model).unwrap()).unwrap_err();
+        assert!(error.reason.contains("version"), "{}", error.reason);
+
+        let capped = ScoringModel { max_score: 10, ..scoring_model() };
+        assert_eq!(score_with_model_json("Gx7!mRq2#vLp".to_string(), serde_json::to_string(&capped).unwrap()).unwrap(), 10);
+    }
+
+    #[test]
+    fn class_alternating_repeats_are_detected_and_penalized() {
+        for password in ["a1a1a1a1", "Z!Z!Z!", "z9z9z9", "Ab1Ab1Ab1"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert!(analysis.pattern_analysis.alternating_pattern, "{:?} not detected", password);
+            assert!(analysis.effective_entropy_bits < analysis.entropy_bits / 2.0, "{:?} not penalized", password);
+        }
+        assert_eq!(alternating_unit_len("a1a1a1a1"), Some(2));
+        assert_eq!(alternating_unit_len("Ab1Ab1Ab1"), Some(3));
+    }
+
+    #[test]
+    fn irregular_or_single_class_repeats_are_not_alternating() {
+        for password in ["a1b7x9q2", "aaaaaa", "a1a1", "abab1abab1"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert!(!analysis.pattern_analysis.alternating_pattern, "{:?}", password);
+        }
+    }
+
+    #[test]
+    fn failure_histogram_counts_critical_codes_across_batch() {
+        let passwords = ["admin", "Gx7!mRq2#vLp", "password", "abc"].map(str::to_string).to_vec();
+        let histogram = failure_reason_histogram(passwords, PasswordPolicy::default());
+
+        let expected: HashMap<String, u32> = [("TOO_SHORT", 2), ("COMMON_PASSWORD", 2), ("LOW_STRENGTH", 3), ("SEQUENTIAL_CHARS", 1)]
+            .into_iter()
+            .map(|(code, count)| (code.to_string(), count))
+            .collect();
+        assert_eq!(histogram, expected);
+    }
+
+    #[test]
+    fn failure_histogram_is_empty_for_compliant_batch() {
+        let passwords = vec!["Gx7!mRq2#vLp".to_string(), "Tq9$wZe4!kNb".to_string()];
+        assert!(failure_reason_histogram(passwords, PasswordPolicy::default()).is_empty());
+    }
+
+    #[test]
+    fn ignored_prefix_suppresses_sequence_in_mandated_part() {
+        let exempt = PasswordPolicy { ignore_first_chars: Some(3), ..Default::default() };
+        let plain = analyze_password("ABC-Gx!mRq9vLp", &PasswordPolicy::default(), &[]);
+        let ignored = analyze_password("ABC-Gx!mRq9vLp", &exempt, &[]);
+
+        assert!(plain.pattern_analysis.sequential_chars > 0);
+        assert_eq!(ignored.pattern_analysis.sequential_chars, 0);
+        assert!(!has_code(&ignored, "SEQUENTIAL_CHARS"));
+        assert_eq!(ignored.pattern_analysis.length, plain.pattern_analysis.length);
+        assert_eq!(ignored.entropy_bits, plain.entropy_bits);
+    }
+
+    #[test]
+    fn ignored_suffix_suppresses_sequence_but_not_elsewhere() {
+        let exempt = PasswordPolicy { ignore_last_chars: Some(3), ..Default::default() };
+        assert_eq!(analyze_password("Gx!mRq9vLp-123", &exempt, &[]).pattern_analysis.sequential_chars, 0);
+        assert!(analyze_password("Gx!abc9vLp-Q7z", &exempt, &[]).pattern_analysis.sequential_chars > 0);
+        assert_eq!(pattern_region("abcdef", &PasswordPolicy { ignore_first_chars: Some(4), ignore_last_chars: Some(4), ..Default::default() }), ("", 4));
+    }
+
+    // The forbidden list is global, so everything touching it stays in this one test
+    #[test]
+    fn large_forbidden_substring_list_matches_case_insensitively() {
+        let mut terms: Vec<String> = (0..5000).map(|i| format!("zorbflux{:05}", i)).collect();
+        terms.push("Acme-Secret".to_string());
+        load_forbidden_substrings(terms).unwrap();
+
+        let analysis = analyze_password("myACME-secret!9Q", &PasswordPolicy::default(), &[]);
+        assert!(analysis.pattern_analysis.contains_forbidden);
+        assert_eq!(analysis.pattern_analysis.forbidden_match.as_deref(), Some("Acme-Secret"));
+        assert!(has_code(&analysis, "FORBIDDEN_SUBSTRING"));
+        assert_eq!(find_forbidden_substring("xxZORBFLUX04321xx").as_deref(), Some("zorbflux04321"));
+        assert_eq!(find_forbidden_substring("Gx7!mRq2#vLp"), None);
+
+        load_forbidden_substrings(vec![" ".to_string()]).unwrap();
+        assert!(!analyze_password("myACME-secret!9Q", &PasswordPolicy::default(), &[]).pattern_analysis.contains_forbidden);
+    }
+
+    #[test]
+    fn score_projection_increases_with_additional_characters() {
+        let projected = |additional_chars| score_if_extended("Gx7!".to_string(), additional_chars, PasswordPolicy::default());
+        assert_eq!(projected(0), analyze_password("Gx7!", &PasswordPolicy::default(), &[]).strength_score);
+        assert!(projected(4) > projected(0));
+        assert!(projected(8) > projected(4));
+        assert!((0..20).all(|n| projected(n + 1) >= projected(n)));
+    }
+
+    #[test]
+    fn normalized_common_match_catches_compound_evasions() {
+        let cases = [
+            ("1drowssap", "password"),
+            ("DROWSSAP!!", "password"),
+            ("p@ssw0rd123", "password"),
+            ("!!nimda", "admin"),
+            ("ytr3wq", "qwerty"),
+            ("4dm1n", "admin"),
+            ("99dr0wss@p", "password"),
+            ("654321", "123456"),
+        ];
+        for (password, base) in cases {
+            assert_eq!(normalized_common_match(password.to_string()).as_deref(), Some(base), "{:?}", password);
+        }
+    }
+
+    #[test]
+    fn normalized_common_match_ignores_unrelated_passwords() {
+        for password in ["Gx7!mRq2#vLp", "passwort", "", "!!!"] {
+            assert_eq!(normalized_common_match(password.to_string()), None, "{:?}", password);
+        }
+    }
+
+    #[test]
+    fn batch_analysis_is_identical_for_every_chunk_size() {
+        let passwords: Vec<String> = (0..50).map(|i| format!("Pw{}!{}", i, "x".repeat(i % 17))).collect();
+        let hashes = |chunk_size| {
+            analyze_batch(passwords.clone(), None, chunk_size).into_iter().map(|analysis| analysis.result_hash).collect::<Vec<_>>()
+        };
+        let unchunked = hashes(None);
+        for chunk_size in [0, 1, 3, 7, 64] {
+            assert_eq!(hashes(Some(chunk_size)), unchunked, "chunk_size {}", chunk_size);
+        }
+    }
+
+    #[test]
+    fn chunked_parallel_map_preserves_order() {
+        let items: Vec<u32> = (0..1000).collect();
+        for chunk_size in [None, Some(0), Some(1), Some(10), Some(999), Some(5000)] {
+            assert_eq!(par_map_chunked(&items, chunk_size, |n| n * 2), items.iter().map(|n| n * 2).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn batch_hashing_covers_every_password_for_any_chunk_size() {
+        let passwords = vec!["alpha-pass".to_string(), "bravo-pass".to_string()];
+        for chunk_size in [None, Some(1)] {
+            let hashes = batch_hash_passwords(passwords.clone(), chunk_size).unwrap();
+            assert_eq!(hashes.len(), passwords.len());
+            for password in &passwords {
+                assert!(verify_password_hash(password.clone(), hashes[password].clone()).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn padding_tokens_are_reported_with_base_strength() {
+        for (password, token, base) in [("password!!!", "!!!", "password"), ("123qwerty", "123", "qwerty"), ("Sunshine!@#$", "!@#$", "Sunshine")] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert_eq!(analysis.padding_token.as_deref(), Some(token), "{:?}", password);
+            let base_score = analyze_password(base, &PasswordPolicy::default(), &[]).strength_score;
+            assert_eq!(analysis.base_strength_score, Some(base_score), "{:?}", password);
+            assert!(has_code(&analysis, "WEAK_BASE_WITH_PADDING"), "{:?}", password);
+        }
+    }
+
+    #[test]
+    fn unpadded_or_too_short_bases_have_no_padding() {
+        for password in ["Gx7!mRq2#vLp", "abc!", "!!!"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert_eq!(analysis.padding_token, None, "{:?}", password);
+            assert_eq!(analysis.base_strength_score, None);
+        }
+    }
+
+    fn crack_policy(min_offline_crack_seconds: f64) -> PasswordPolicy {
+        PasswordPolicy { min_offline_crack_seconds: Some(min_offline_crack_seconds), ..Default::default() }
+    }
+
+    #[test]
+    fn crack_time_minimum_applies_around_the_estimate() {
+        for password in ["password1", "Tr0ub4dor&3", "Gx7!mRq2#vLpWz9$"] {
+            let seconds = estimate_offline_crack_seconds(password);
+            assert!(!has_code(&analyze_password(password, &crack_policy(seconds * 0.5), &[]), "CRACKS_TOO_FAST"), "{:?}", password);
+            assert!(has_code(&analyze_password(password, &crack_policy(seconds * 2.0), &[]), "CRACKS_TOO_FAST"), "{:?}", password);
+        }
+    }
+
+    #[test]
+    fn crack_time_minimum_separates_weak_from_strong() {
+        let weak = analyze_password("password1", &crack_policy(1.0), &[]);
+        let item = weak.feedback_items.iter().find(|item| item.code == "CRACKS_TOO_FAST").unwrap();
+        assert!(item.message.contains("less than a second"));
+        assert!(!weak.is_compliant);
+
+        assert!(!has_code(&analyze_password("Gx7!mRq2#vLpWz9$", &crack_policy(86_400.0), &[]), "CRACKS_TOO_FAST"));
+    }
+
+    #[test]
+    fn durations_are_described_in_the_largest_whole_unit() {
+        assert_eq!(describe_duration(0.5, FeedbackLanguage::English), "less than a second");
+        assert_eq!(describe_duration(90.0, FeedbackLanguage::English), "about 1 minutes");
+        assert_eq!(describe_duration(3.0 * 86_400.0, FeedbackLanguage::English), "about 3 days");
+        assert_eq!(describe_duration(3.0 * 86_400.0, FeedbackLanguage::Spanish), "unos 3 días");
+    }
+
+    #[test]
+    fn breach_lookup_key_is_deterministic_and_keyed() {
+        let key = |password: &str, corpus_key: &[u8]| breach_lookup_key(password.to_string(), corpus_key.to_vec()).unwrap();
+        assert_eq!(key("hunter2", b"corpus-key"), key("hunter2", b"corpus-key"));
+        assert_eq!(key("hunter2", b"corpus-key").len(), 64);
+        assert_ne!(key("hunter2", b"corpus-key"), key("hunter2", b"rotated-key"));
+        assert_ne!(key("hunter2", b"corpus-key"), key("Hunter2", b"corpus-key"));
+        assert_ne!(key("hunter2", b"corpus-key"), hash_password_sha1("hunter2".to_string()).unwrap().to_lowercase());
+        assert!(breach_lookup_key("hunter2".to_string(), Vec::new()).is_err());
+    }
+
+    #[test]
+    fn breach_lookup_key_matches_hmac_sha256_vector() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            breach_lookup_key("what do ya want for nothing?".to_string(), b"Jefe".to_vec()).unwrap(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn first_letter_only_capitalization_is_flagged_and_scores_lower() {
+        let predictable = analyze_password("Password1!", &PasswordPolicy::default(), &[]);
+        let mixed = analyze_password("pAsswOrd1!", &PasswordPolicy::default(), &[]);
+
+        assert!(predictable.pattern_analysis.only_first_capitalized);
+        assert!(has_code(&predictable, "PREDICTABLE_CAPITALIZATION"));
+        assert!(!mixed.pattern_analysis.only_first_capitalized);
+        assert!(!has_code(&mixed, "PREDICTABLE_CAPITALIZATION"));
+        assert!(predictable.strength_score < mixed.strength_score);
+    }
+
+    #[test]
+    fn all_caps_or_single_letter_is_not_first_capitalized() {
+        for password in ["PASSWORD1!", "P1234567", "password1!"] {
+            assert!(!is_only_first_capitalized(password), "{:?}", password);
+        }
+    }
+
+    fn now_ms() -> i64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as i64
+    }
+
+    #[test]
+    fn hash_staleness_flips_at_the_age_boundary() {
+        let day_ms = 86_400_000;
+        let minute_ms = 60_000;
+        assert!(is_hash_stale(now_ms() - 365 * day_ms - minute_ms, 365));
+        assert!(!is_hash_stale(now_ms() - 365 * day_ms + minute_ms, 365));
+        assert!(!is_hash_stale(now_ms(), 365));
+        assert!(is_hash_stale(now_ms() - minute_ms, 0));
+    }
+
+    #[test]
+    fn future_hash_timestamps_are_never_stale() {
+        assert!(!is_hash_stale(now_ms() + 86_400_000, 0));
+        assert!(!is_hash_stale(i64::MAX, 0));
+        assert!(is_hash_stale(i64::MIN, u32::MAX));
+    }
+
+    #[test]
+    fn weakest_passwords_returns_k_lowest_in_ascending_order() {
+        let passwords: Vec<String> = ["Gx7!mRq2#vLp", "admin", "Password1!", "aaaa", "Tq9$wZe4!kNb"].map(str::to_string).to_vec();
+        let weakest = weakest_passwords(passwords.clone(), 2).unwrap();
+
+        assert_eq!(weakest.iter().map(|entry| entry.index).collect::<Vec<_>>(), vec![3, 1]);
+        assert!(weakest.windows(2).all(|pair| pair[0].strength_score <= pair[1].strength_score));
+        assert_eq!(weakest[0].top_reason.as_deref(), Some("TOO_SHORT"));
+        assert_eq!(weakest[1].top_reason.as_deref(), Some("COMMON_PASSWORD"));
+
+        let all = weakest_passwords(passwords.clone(), 10).unwrap();
+        assert_eq!(all.len(), passwords.len());
+        assert!(all.windows(2).all(|pair| pair[0].strength_score <= pair[1].strength_score));
+        assert!(weakest_passwords(passwords, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn weakest_passwords_keep_input_order_on_ties() {
+        let passwords = vec!["zqxwvmkp".to_string(), "mkpzqxwv".to_string(), "pzqxwvmk".to_string()];
+        let indices: Vec<u32> = weakest_passwords(passwords, 3).unwrap().iter().map(|entry| entry.index).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn studly_caps_are_flagged_and_lose_the_case_bonus() {
+        let irregular = analyze_password("paSSwOrd", &PasswordPolicy::default(), &[]);
+        assert!(!irregular.pattern_analysis.studly_caps);
+        assert!(!has_code(&irregular, "PREDICTABLE_CASE_PATTERN"));
+
+        for password in ["pAsSwOrD", "PaSsWoRd"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert!(analysis.pattern_analysis.studly_caps, "{:?}", password);
+            assert!(has_code(&analysis, "PREDICTABLE_CASE_PATTERN"));
+            assert!(analysis.strength_score < irregular.strength_score, "{:?}", password);
+        }
+    }
+
+    #[test]
+    fn studly_caps_skip_non_letters_and_need_four_letters() {
+        assert!(is_studly_caps("PaSs1WoRd"));
+        assert!(!is_studly_caps("aB1"));
+        assert!(!is_studly_caps("PassWord"));
+    }
+
+    const FAST_ARGON2: Argon2Params = Argon2Params { memory_kib: 1024, iterations: 1, parallelism: 1 };
+
+    #[test]
+    fn salt_length_32_round_trips() {
+        let hash = hash_password_with_params("hunter2".to_string(), FAST_ARGON2, Some(32)).unwrap();
+        let salt = PasswordHash::new(&hash).unwrap().salt.unwrap();
+        assert_eq!(salt.as_str().len(), 43);
+        assert!(verify_password_hash("hunter2".to_string(), hash.clone()).unwrap());
+        assert!(!verify_password_hash("hunter3".to_string(), hash).unwrap());
+
+        let default_salt = hash_password_with_params("hunter2".to_string(), FAST_ARGON2, None).unwrap();
+        assert_eq!(PasswordHash::new(&default_salt).unwrap().salt.unwrap().as_str().len(), 22);
+    }
+
+    #[test]
+    fn salt_length_outside_argon2_range_is_rejected() {
+        for salt_len in [0, 7, 49] {
+            let error = hash_password_with_params("hunter2".to_string(), FAST_ARGON2, Some(salt_len)).unwrap_err();
+            assert!(error.reason.contains("salt_len"), "{}", salt_len);
+        }
+        assert!(hash_password_with_params("hunter2".to_string(), FAST_ARGON2, Some(48)).is_ok());
+    }
+
+    #[test]
+    fn relaxing_only_min_length_flips_compliance() {
+        let policy = PasswordPolicy { min_length: Some(16), ..Default::default() };
+        let relaxation = minimum_relaxation("Gx7!mRq2#vLp".to_string(), policy.clone());
+
+        assert!(!relaxation.already_compliant);
+        assert_eq!(relaxation.relaxations.len(), 1);
+        assert_eq!(relaxation.relaxations[0].constraint, "minLength");
+        assert_eq!(relaxation.relaxations[0].relaxed_value, 12.0);
+
+        let relaxed = PasswordPolicy { min_length: Some(12), ..policy };
+        assert!(analyze_password("Gx7!mRq2#vLp", &relaxed, &[]).is_compliant);
+    }
+
+    #[test]
+    fn minimum_relaxation_reports_compliant_and_unfixable_passwords() {
+        assert!(minimum_relaxation("Gx7!mRq2#vLp".to_string(), PasswordPolicy::default()).already_compliant);
+
+        let common = minimum_relaxation("password".to_string(), PasswordPolicy::default());
+        assert!(!common.already_compliant);
+        assert!(common.relaxations.is_empty());
+    }
+
+    #[test]
+    fn nfc_marker_round_trips_across_composition() {
+        let hash = hash_password_normalized("cafe\u{301}".to_string(), None).unwrap();
+        assert!(hash.starts_with("nfc$$argon2id$"));
+        assert!(verify_password_normalized("caf\u{e9}".to_string(), hash.clone()).unwrap());
+        assert!(verify_password_normalized("cafe\u{301}".to_string(), hash.clone()).unwrap());
+        assert!(!verify_password_normalized("cafe".to_string(), hash).unwrap());
+    }
+
+    #[test]
+    fn nfkc_marker_round_trips_compatibility_forms() {
+        let hash = hash_password_normalized("\u{FB01}le".to_string(), Some("NFKC".to_string())).unwrap();
+        assert!(hash.starts_with("nfkc$$argon2id$"));
+        assert!(verify_password_normalized("file".to_string(), hash.clone()).unwrap());
+        assert!(verify_password_normalized("\u{FB01}le".to_string(), hash).unwrap());
+
+        let nfc_hash = format!("nfc${}", hash_password("\u{FB01}le".to_string()).unwrap());
+        assert!(!verify_password_normalized("file".to_string(), nfc_hash).unwrap());
+    }
+
+    #[test]
+    fn normalized_verify_handles_unmarked_and_unknown_markers() {
+        let plain = hash_password("cafe\u{301}".to_string()).unwrap();
+        assert!(verify_password_normalized("cafe\u{301}".to_string(), plain.clone()).unwrap());
+        assert!(!verify_password_normalized("caf\u{e9}".to_string(), plain.clone()).unwrap());
+
+        assert!(verify_password_normalized("x".to_string(), format!("nfd${}", plain)).is_err());
+        assert!(hash_password_normalized("x".to_string(), Some("NFD".to_string())).is_err());
+    }
+
+    #[test]
+    fn shifted_keyboard_walks_are_detected_and_penalized() {
+        for password in ["!QAZ2wsx", "@WSX#EDC", "1qaZXsw2", "zxcVBNM,"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert!(analysis.pattern_analysis.shift_keyboard_walk, "{:?} not detected", password);
+            assert!(has_code(&analysis, "SHIFT_KEYBOARD_WALK"));
+
+            let mut unpenalized = analysis.pattern_analysis.clone();
+            unpenalized.shift_keyboard_walk = false;
+            let unpenalized_score = calculate_strength_score(&unpenalized, analysis.effective_entropy_bits, &ScoringModel::default());
+            assert!(analysis.strength_score < unpenalized_score, "{:?} not penalized", password);
+        }
+    }
+
+    #[test]
+    fn capitalized_words_and_plain_walks_are_not_shift_walks() {
+        for password in ["Fred2024!", "Were9#kz", "Sweden8!q", "Qwerty12", "Asdf!9kz", "qwer1234", "Gx7!mRq2#vLp"] {
+            assert!(!has_shift_keyboard_walk(password), "{:?} wrongly flagged", password);
+        }
+        let analysis = analyze_password("Fred2024!", &PasswordPolicy::default(), &[]);
+        assert!(!analysis.pattern_analysis.shift_keyboard_walk);
+        assert!(!has_code(&analysis, "SHIFT_KEYBOARD_WALK"));
+    }
+
+    #[test]
+    fn identical_passwords_share_a_fingerprint_and_count_as_reuse() {
+        let passwords = ["hunter2", "letmein", "hunter2", "s3cret", "hunter2", "letmein"].map(str::to_string).to_vec();
+        let batch = batch_fingerprint_sha256(passwords).unwrap();
+
+        assert_eq!(batch.fingerprints.len(), 6);
+        assert_eq!(batch.fingerprints[0], batch.fingerprints[2]);
+        assert_eq!(batch.fingerprints[0], batch.fingerprints[4]);
+        assert_eq!(batch.fingerprints[1], batch.fingerprints[5]);
+        assert_ne!(batch.fingerprints[0], batch.fingerprints[1]);
+        assert_eq!(batch.fingerprints[0], "f52fbd32b2b3b86ff88ef6c490628285f482af15ddcb29541f94bcf526a3f6c7");
+        assert_eq!(batch.distinct_count, 3);
+        assert_eq!(batch.max_reuse_count, 3);
+    }
+
+    #[test]
+    fn empty_fingerprint_batch_has_no_reuse() {
+        let batch = batch_fingerprint_sha256(Vec::new()).unwrap();
+        assert!(batch.fingerprints.is_empty());
+        assert_eq!(batch.distinct_count, 0);
+        assert_eq!(batch.max_reuse_count, 0);
+    }
+
+    #[test]
+    fn email_shaped_passwords_fail_when_forbidden() {
+        let policy = PasswordPolicy { forbid_email_format: Some(true), ..Default::default() };
+        let analysis = analyze_password("user@example.com", &policy, &[]);
+        assert!(has_code(&analysis, "LOOKS_LIKE_EMAIL"));
+        assert!(!analysis.is_compliant);
+        assert!(!has_code(&analyze_password("user@example.com", &PasswordPolicy::default(), &[]), "LOOKS_LIKE_EMAIL"));
+    }
+
+    #[test]
+    fn passwords_with_an_at_sign_but_not_email_shaped_pass() {
+        let policy = PasswordPolicy { forbid_email_format: Some(true), ..Default::default() };
+        for password in ["Gx7@mRq2#vLp", "me@home", "@Rq2#vLp.io", "a b@c.com"] {
+            assert!(!has_code(&analyze_password(password, &policy, &[]), "LOOKS_LIKE_EMAIL"), "{:?}", password);
+        }
+        assert!(analyze_password("Gx7@mRq2#vLp", &policy, &[]).is_compliant);
+    }
+
+    #[test]
+    fn embedded_resources_are_non_empty_and_hashed() {
+        let resources = embedded_resources();
+        let names: Vec<&str> = resources.iter().map(|resource| resource.name.as_str()).collect();
+        assert_eq!(names, ["common_passwords", "padding_tokens", "common_names", "common_phrases"]);
+
+        for resource in &resources {
+            assert!(resource.entry_count > 0, "{}", resource.name);
+            assert_eq!(resource.sha256.len(), 64);
+            assert_eq!(resource.version, env!("CARGO_PKG_VERSION"));
+        }
+        assert_eq!(resources[0].entry_count as usize, COMMON_PASSWORDS.len());
+        assert_eq!(resources[0].sha256, hex::encode(Sha256::digest(COMMON_PASSWORDS.join("\n").as_bytes())));
+    }
+
+    #[test]
+    fn analyze_and_hash_returns_hash_for_compliant_password() {
+        let mut task = AnalyzeAndHash { password: "Gx7!mRq2#vLp".to_string(), policy: PasswordPolicy::default() };
+        let result = task.compute().unwrap();
+
+        assert!(result.analysis.is_compliant);
+        assert_eq!(result.analysis.result_hash, analyze_password("Gx7!mRq2#vLp", &PasswordPolicy::default(), &[]).result_hash);
+        assert!(verify_password_hash("Gx7!mRq2#vLp".to_string(), result.hash.unwrap()).unwrap());
+        assert!(task.password.is_empty());
+    }
+
+    #[test]
+    fn analyze_and_hash_discards_hash_for_non_compliant_password() {
+        let mut task = AnalyzeAndHash { password: "password".to_string(), policy: PasswordPolicy::default() };
+        let result = task.compute().unwrap();
+
+        assert!(!result.analysis.is_compliant);
+        assert!(result.hash.is_none());
+        assert!(task.password.is_empty());
+    }
+
+    #[test]
+    fn name_based_passwords_are_flagged() {
+        for password in ["Michael1", "j3ssica", "!Amanda99", "EMMA2020", "w1ll1am"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert!(analysis.pattern_analysis.contains_common_name, "{:?} not flagged", password);
+            assert!(has_code(&analysis, "COMMON_NAME"));
+        }
+    }
+
+    #[test]
+    fn passwords_not_built_on_a_name_are_not_flagged() {
+        for password in ["Gx7!mRq2#vLp", "Michaelangelo", "password1", "zqxwvmkp"] {
+            assert!(!is_common_name_based(password), "{:?} wrongly flagged", password);
+        }
+    }
+
+    #[test]
+    fn result_hash_is_stable_across_runs_and_ignores_timing() {
+        let first = check_password_policy("Tr0ub4dor&3".to_string(), None, None).unwrap();
+        let mut second = check_password_policy("Tr0ub4dor&3".to_string(), None, None).unwrap();
+        assert_eq!(first.result_hash, second.result_hash);
+        assert_eq!(first.result_hash.len(), 64);
+
+        second.analysis_time_ms += 1000;
+        assert_eq!(analysis_result_hash(&second), first.result_hash);
+    }
+
+    #[test]
+    fn result_hash_changes_with_the_result() {
+        let base = analyze_password("Tr0ub4dor&3", &PasswordPolicy::default(), &[]);
+        let stricter = analyze_password("Tr0ub4dor&3", &PasswordPolicy { min_length: Some(16), ..Default::default() }, &[]);
+        let other = analyze_password("Tr0ub4dor&3x", &PasswordPolicy::default(), &[]);
+        assert_ne!(base.result_hash, stricter.result_hash);
+        assert_ne!(base.result_hash, other.result_hash);
+    }
+
+    #[test]
+    fn bcrypt_hash_migrates_to_argon2_on_successful_verify() {
+        let stored = bcrypt::hash("hunter2", 4).unwrap();
+        let result = verify_and_migrate_algo("hunter2".to_string(), stored, "argon2".to_string(), FAST_ARGON2_JSON.to_string()).unwrap();
+
+        assert!(result.valid);
+        let new_hash = result.new_hash.unwrap();
+        assert!(new_hash.starts_with("$argon2id$v=19$m=1024,t=1,p=1$"));
+        assert!(verify_password_hash("hunter2".to_string(), new_hash).unwrap());
+    }
+
+    #[test]
+    fn migration_needs_a_valid_password_and_a_different_algorithm() {
+        let stored = bcrypt::hash("hunter2", 4).unwrap();
+        let wrong = verify_and_migrate_algo("hunter3".to_string(), stored.clone(), "argon2".to_string(), String::new()).unwrap();
+        assert!(!wrong.valid);
+        assert!(wrong.new_hash.is_none());
+
+        let same = verify_and_migrate_algo("hunter2".to_string(), stored, "bcrypt".to_string(), String::new()).unwrap();
+        assert!(same.valid);
+        assert!(same.new_hash.is_none());
+
+        let argon2_stored = hash_password_with_params("hunter2".to_string(), FAST_ARGON2, None).unwrap();
+        let current = verify_and_migrate_algo("hunter2".to_string(), argon2_stored, "argon2".to_string(), String::new()).unwrap();
+        assert!(current.valid);
+        assert!(current.new_hash.is_none());
+    }
+
+    #[test]
+    fn migration_rejects_unknown_hash_formats() {
+        assert!(verify_and_migrate_algo("hunter2".to_string(), "5f4dcc3b5aa765d61d8327deb882cf99".to_string(), "argon2".to_string(), String::new()).is_err());
+        assert_eq!(hash_algorithm("$2y$10$abc"), Some("bcrypt"));
+        assert_eq!(hash_algorithm("$argon2i$v=19$..."), Some("argon2"));
+    }
+
+    #[test]
+    fn entropy_mode_score_never_drops_as_password_grows() {
+        let policy = PasswordPolicy { scoring_mode: Some(ScoringMode::Entropy), ..Default::default() };
+        let full = "Gx7!mRq2#vLpWz9$Hb4&";
+        let mut previous = (0.0, 0);
+        for end in 1..=full.len() {
+            let analysis = analyze_password(&full[..end], &policy, &[]);
+            assert!(analysis.effective_entropy_bits >= previous.0);
+            assert!(analysis.strength_score >= previous.1, "score dropped at {:?}", &full[..end]);
+            previous = (analysis.effective_entropy_bits, analysis.strength_score);
+        }
+        assert_eq!(previous.1, 100);
+    }
+
+    #[test]
+    fn entropy_mode_scales_effective_entropy_against_target() {
+        let model = ScoringModel { mode: ScoringMode::Entropy, ..ScoringModel::default() };
+        let patterns = analyze_patterns("abcdefgh", &PasswordPolicy::default());
+        assert_eq!(calculate_strength_score(&patterns, 40.0, &model), 50);
+        assert_eq!(calculate_strength_score(&patterns, 200.0, &model), 100);
+        assert_eq!(calculate_strength_score(&patterns, -5.0, &model), 0);
+    }
+
+    #[test]
+    fn score_projection_grows_steadily_in_entropy_mode() {
+        let policy = PasswordPolicy { scoring_mode: Some(ScoringMode::Entropy), ..Default::default() };
+        let projected = |additional_chars| score_if_extended("Gx7!".to_string(), additional_chars, policy.clone());
+        assert!(projected(1) > projected(0));
+        assert!(projected(2) > projected(1));
+        assert_eq!(projected(100), 100);
+    }
+
+    #[test]
+    fn common_phrases_without_spaces_are_flagged() {
+        for password in ["correcthorsebatterystaple", "letmeinplease", "LetMeInPlease", "correct horse battery staple"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert!(analysis.pattern_analysis.concatenated_phrase, "{:?} not flagged", password);
+            assert!(has_code(&analysis, "COMMON_PHRASE"));
+            assert!(!analysis.is_compliant);
+        }
+    }
+
+    #[test]
+    fn partial_or_unrelated_phrases_are_not_flagged() {
+        for password in ["correcthorsebattery", "letmeinplease2", "Gx7!mRq2#vLp"] {
+            assert!(!is_concatenated_phrase(password), "{:?} wrongly flagged", password);
+        }
+    }
+
+    fn full_context() -> UserContext {
+        UserContext {
+            username: Some("jsmith".to_string()),
+            email: Some("alice.w@example.com".to_string()),
+            full_name: Some("Robert Tables".to_string()),
+            date_of_birth: Some("1990-07-05".to_string()),
+            service_terms: Some(vec!["Acmebank".to_string()]),
+        }
+    }
+
+    #[test]
+    fn each_context_field_triggers_its_check() {
+        let cases = [
+            ("Xy!jsmith9Q", "CONTAINS_USERNAME"),
+            ("Q9!alice.wZz", "CONTAINS_EMAIL"),
+            ("Q9!tablesZz", "CONTAINS_NAME"),
+            ("Q9!zz0705Kp", "CONTAINS_DOB"),
+            ("Q9!acmebankZz", "CONTAINS_SERVICE_NAME"),
+            ("htimsj", "IDENTITY_TRANSFORMED"),
+        ];
+        for (password, code) in cases {
+            let analysis = check_password_policy_with_context(password.to_string(), None, full_context()).unwrap();
+            assert!(has_code(&analysis, code), "{:?} should trigger {}", password, code);
+            assert!(!analysis.is_compliant);
+        }
+    }
+
+    #[test]
+    fn unrelated_password_passes_context_checks() {
+        let analysis = check_password_policy_with_context("Gx7!mRq2#vLp".to_string(), None, full_context()).unwrap();
+        assert!(analysis.is_compliant);
+        let empty = check_password_policy_with_context("Gx7!mRq2#vLp".to_string(), None, UserContext::default()).unwrap();
+        assert_eq!(empty.result_hash, analysis.result_hash);
+    }
+
+    #[test]
+    fn malformed_date_of_birth_is_rejected() {
+        for date in ["07/05/1990", "1990-13-05", "90-07-05", "1990-07"] {
+            let context = UserContext { date_of_birth: Some(date.to_string()), ..Default::default() };
+            assert!(check_password_policy_with_context("Gx7!mRq2#vLp".to_string(), None, context).is_err(), "{:?}", date);
+        }
+    }
+
+    #[test]
+    fn critical_feedback_precedes_info() {
+        let analysis = analyze_password("admin", &PasswordPolicy::default(), &[]);
+        let position = |code: &str| analysis.feedback_items.iter().position(|item| item.code == code).unwrap();
+        assert!(position("COMMON_PASSWORD") < position("MISSING_SYMBOL"));
+        assert_eq!(analysis.feedback_items[0].severity, Severity::Critical);
+        assert!(analysis.feedback_items.windows(2).all(|pair| pair[0].severity <= pair[1].severity));
+        assert_eq!(analysis.feedback, analysis.feedback_items.iter().map(|item| item.message.clone()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn leet_forms_of_common_passwords_get_their_own_code() {
+        for (password, base) in [("p@ssw0rd", "password"), ("P@$$W0RD", "password"), ("4dm1n", "admin"), ("qw3rty", "qwerty")] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert_eq!(analysis.pattern_analysis.leet_common_base.as_deref(), Some(base), "{:?}", password);
+            let item = analysis.feedback_items.iter().find(|item| item.code == "LEET_COMMON").unwrap();
+            assert_eq!(item.severity, Severity::Critical);
+            assert!(item.message.contains(&format!("'{}'", base)));
+            assert!(!item.message.contains(password));
+            assert!(!has_code(&analysis, "COMMON_PASSWORD"));
+        }
+    }
+
+    #[test]
+    fn plain_common_passwords_are_not_leet() {
+        let analysis = analyze_password("password", &PasswordPolicy::default(), &[]);
+        assert!(has_code(&analysis, "COMMON_PASSWORD"));
+        assert!(!has_code(&analysis, "LEET_COMMON"));
+        assert_eq!(leet_common_base("Gx7!mRq2#vLp"), None);
+    }
+
+    #[test]
+    fn breach_weight_scales_from_ignored_to_maximum_risk() {
+        let breach = || Some(BreachInfo { count: 1000, most_recent_year: Some(current_year()) });
+        let clean = risk_for("Gx7!mRq2#vLp", None, None).risk_score;
+        let ignored = risk_for("Gx7!mRq2#vLp", breach(), Some(0.0)).risk_score;
+        let default = risk_for("Gx7!mRq2#vLp", breach(), Some(0.5)).risk_score;
+        let maximum = risk_for("Gx7!mRq2#vLp", breach(), Some(1.0)).risk_score;
+
+        assert_eq!(ignored, clean);
+        assert!(default > clean && default < 100);
+        assert_eq!(maximum, 100);
+        assert_eq!(risk_for("Gx7!mRq2#vLp", breach(), None).risk_score, default);
+        assert_eq!(risk_for("Gx7!mRq2#vLp", breach(), Some(f64::NAN)).risk_score, default);
+        assert_eq!(risk_for("Gx7!mRq2#vLp", breach(), Some(7.0)).risk_score, 100);
+    }
+
+    #[test]
+    fn breach_weight_has_no_effect_without_a_breach() {
+        let clean = risk_for("Gx7!mRq2#vLp", None, None).risk_score;
+        assert_eq!(risk_for("Gx7!mRq2#vLp", None, Some(1.0)).risk_score, clean);
+        assert_eq!(risk_for("Gx7!mRq2#vLp", Some(BreachInfo { count: 0, most_recent_year: None }), Some(1.0)).risk_score, clean);
+    }
+
+    fn telemetry_for(password: &str) -> TelemetryRecord {
+        telemetry_record(analyze_password(password, &PasswordPolicy::default(), &[]))
+    }
+
+    #[test]
+    fn telemetry_record_holds_only_coarse_aggregates() {
+        let password = "Gx7!mRq2#vLp";
+        let record = telemetry_for(password);
+        let json = serde_json::to_value(&record).unwrap();
+
+        let mut fields: Vec<&String> = json.as_object().unwrap().keys().collect();
+        fields.sort();
+        assert_eq!(fields, [
+            "blocklisted", "class_count", "common_word", "compliant", "entropy_bucket", "length_bucket", "low_strength",
+            "personal_info", "predictable_case", "repeated", "score_band", "sequential", "too_short",
+        ]);
+        let text = json.to_string();
+        for window in password.as_bytes().windows(3) {
+            assert!(!text.contains(std::str::from_utf8(window).unwrap()));
+        }
+        assert!(record.score_band.is_multiple_of(20));
+        assert!(record.entropy_bucket.is_multiple_of(20) && record.entropy_bucket <= 100);
+        assert_eq!(record.length_bucket, "12-15");
+        assert_eq!(record.class_count, 4);
+    }
+
+    #[test]
+    fn similar_passwords_give_identical_telemetry() {
+        assert_eq!(
+            serde_json::to_string(&telemetry_for("Gx7!mRq2#vLp")).unwrap(),
+            serde_json::to_string(&telemetry_for("Tq9$wZe4!kNb")).unwrap()
+        );
+    }
+
+    #[test]
+    fn telemetry_flags_weakness_categories() {
+        let record = telemetry_for("admin");
+        assert!(record.common_word && record.too_short && record.low_strength && !record.compliant);
+        assert_eq!(record.length_bucket, "<8");
+        assert!(telemetry_for("abcdefgh").sequential);
+        assert!(telemetry_for("a1a1a1a1").repeated);
+        assert!(telemetry_for("Password1!").predictable_case);
+    }
+
+    #[test]
+    fn case_inversions_of_previous_password_are_detected() {
+        let inverted = |previous: &str, candidate: &str| is_case_inversion(previous.to_string(), candidate.to_string());
+        assert!(inverted("Secret", "sECRET"));
+        assert!(inverted("Secret42!", "sECRET42!"));
+        assert!(inverted("Secret", "secret"));
+        assert!(inverted("Straße", "STRAßE"));
+    }
+
+    #[test]
+    fn genuine_changes_are_not_case_inversions() {
+        let inverted = |previous: &str, candidate: &str| is_case_inversion(previous.to_string(), candidate.to_string());
+        assert!(!inverted("Secret", "Secret"));
+        assert!(!inverted("Secret", "Secret1"));
+        assert!(!inverted("Secret", "Sekret"));
+        assert!(!inverted("Secret42!", "sECRET43!"));
+    }
+
+    #[test]
+    fn policy_columns_line_up_with_input() {
+        let passwords: Vec<String> = ["admin", "Gx7!mRq2#vLp", "abc", "Tq9$wZe4!kNbXy7&"].map(str::to_string).to_vec();
+        let columns = batch_policy_columns(passwords.clone(), PasswordPolicy::default());
+
+        for length in [columns.scores.len(), columns.compliant.len(), columns.entropy.len(), columns.length.len()] {
+            assert_eq!(length, passwords.len());
+        }
+        for (i, password) in passwords.iter().enumerate() {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert_eq!(columns.scores[i], analysis.strength_score);
+            assert_eq!(columns.compliant[i], analysis.is_compliant);
+            assert_eq!(columns.entropy[i], analysis.entropy_bits);
+            assert_eq!(columns.length[i], password.len() as u32);
+        }
+        assert!(batch_policy_columns(Vec::new(), PasswordPolicy::default()).scores.is_empty());
+    }
+
+    #[test]
+    fn work_units_compare_known_parameter_sets() {
+        let owasp = Argon2Params { memory_kib: 19_456, iterations: 2, parallelism: 1 };
+        let rfc_9106 = Argon2Params { memory_kib: 65_536, iterations: 3, parallelism: 4 };
+
+        assert_eq!(argon2_work_units(owasp), 38.0);
+        assert_eq!(argon2_work_units(rfc_9106), 192.0);
+        assert!((argon2_work_units(rfc_9106) / argon2_work_units(owasp) - 5.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn work_units_ignore_parallelism() {
+        let single = Argon2Params { memory_kib: 65_536, iterations: 3, parallelism: 1 };
+        let quad = Argon2Params { parallelism: 4, ..single };
+        assert_eq!(argon2_work_units(single), argon2_work_units(quad));
+        assert_eq!(argon2_work_units(Argon2Params::default()), 38.0);
+    }
+
+    fn banned(sequences: &[&str]) -> PasswordPolicy {
+        PasswordPolicy { banned_sequences: Some(sequences.iter().map(|s| s.to_string()).collect()), ..Default::default() }
+    }
+
+    #[test]
+    fn wildcard_sequence_matches_digit_run() {
+        let policy = banned(&["SKU-####"]);
+        let analysis = analyze_password("Qx!sku-4821zz", &policy, &[]);
+        assert_eq!(analysis.pattern_analysis.contains_banned_sequence.as_deref(), Some("SKU-####"));
+        assert!(has_code(&analysis, "BANNED_SEQUENCE"));
+        assert!(!analysis.is_compliant);
+
+        assert_eq!(analyze_password("Qx!SKU-48a1zz", &policy, &[]).pattern_analysis.contains_banned_sequence, None);
+        assert_eq!(analyze_password("Qx!SKU-482zz", &policy, &[]).pattern_analysis.contains_banned_sequence, None);
+    }
+
+    #[test]
+    fn first_matching_banned_sequence_is_reported() {
+        let sequences = ["zz??zz".to_string(), "ab?d".to_string(), "x".to_string()];
+        assert_eq!(find_banned_sequence("QabXdzz12zz", &sequences).as_deref(), Some("zz??zz"));
+        assert_eq!(find_banned_sequence("QabXd", &sequences).as_deref(), Some("ab?d"));
+        assert_eq!(find_banned_sequence("Q9!", &sequences), None);
+        assert_eq!(find_banned_sequence("Q9!", &[String::new()]), None);
+    }
+
+    #[test]
+    fn localized_feedback_keeps_codes_and_translates_messages() {
+        let policy = PasswordPolicy {
+            min_offline_crack_seconds: Some(86_400.0),
+            require_interior_symbol: Some(true),
+            max_class_run: Some(3),
+            ..Default::default()
+        };
+        for password in ["admin", "p@ssw0rd", "password!!!", "abcde12345", "Password1!", "pAsSwOrD"] {
+            let english = check_password_policy_localized(password.to_string(), Some(policy.clone()), "en".to_string()).unwrap();
+            let spanish = check_password_policy_localized(password.to_string(), Some(policy.clone()), "es-MX".to_string()).unwrap();
+
+            assert_eq!(english.feedback_items.len(), spanish.feedback_items.len());
+            for (en, es) in english.feedback_items.iter().zip(&spanish.feedback_items) {
+                assert_eq!(en.code, es.code);
+                assert_eq!(en.severity, es.severity);
+                assert_ne!(en.message, es.message, "{} untranslated", en.code);
+            }
+            assert_eq!(spanish.feedback, spanish.feedback_items.iter().map(|item| item.message.clone()).collect::<Vec<_>>());
+            assert_eq!(spanish.result_hash, analysis_result_hash(&spanish));
+        }
+    }
+
+    #[test]
+    fn spanish_crack_time_message_includes_the_estimate() {
+        let policy = PasswordPolicy { min_offline_crack_seconds: Some(1e12), ..Default::default() };
+        for (password, expected) in [("admin", "menos de un segundo"), ("Tr0ub4dor&3x", "unos ")] {
+            let spanish = check_password_policy_localized(password.to_string(), Some(policy.clone()), "es".to_string()).unwrap();
+            let item = spanish.feedback_items.iter().find(|item| item.code == "CRACKS_TOO_FAST").unwrap();
+            let estimate = describe_duration(estimate_offline_crack_seconds(password), FeedbackLanguage::Spanish);
+            assert!(estimate.starts_with(expected), "{:?}", estimate);
+            assert!(item.message.contains(&estimate), "{:?} lacks {:?}", item.message, estimate);
+        }
+    }
+
+    #[test]
+    fn unsupported_locale_is_rejected() {
+        assert!(check_password_policy_localized("admin".to_string(), None, "fr".to_string()).is_err());
+        assert!(check_password_policy_localized("admin".to_string(), None, "EN_us".to_string()).is_ok());
+    }
+
+    #[test]
+    fn tightening_min_length_reports_newly_failing() {
+        let passwords: Vec<String> = ["Gx7!mRq2#v", "Tq9$wZe4!k", "Gx7!mRq2#vLp", "admin"].map(str::to_string).to_vec();
+        let old_policy = PasswordPolicy { min_length: Some(8), ..Default::default() };
+        let new_policy = PasswordPolicy { min_length: Some(12), ..Default::default() };
+
+        let impact = policy_impact(passwords.clone(), old_policy.clone(), new_policy.clone());
+        assert_eq!((impact.newly_failing, impact.newly_passing, impact.unchanged), (2, 0, 2));
+
+        let reverted = policy_impact(passwords, new_policy, old_policy);
+        assert_eq!((reverted.newly_failing, reverted.newly_passing, reverted.unchanged), (0, 2, 2));
+    }
+
+    #[test]
+    fn same_seed_generates_same_compliant_code() {
+        let seed = b"provisioning-seed-0001".to_vec();
+        let policy = PasswordPolicy { min_length: Some(20), require_interior_symbol: Some(true), ..Default::default() };
+        let first = generate_from_seed(seed.clone(), policy.clone()).unwrap();
+
+        assert_eq!(generate_from_seed(seed, policy.clone()).unwrap(), first);
+        assert_eq!(first.chars().count(), 20);
+        assert!(analyze_password(&first, &policy, &[]).is_compliant);
+    }
+
+    #[test]
+    fn different_seeds_generate_different_codes() {
+        let generate = |seed: &[u8]| generate_from_seed(seed.to_vec(), PasswordPolicy::default()).unwrap();
+        assert_ne!(generate(b"provisioning-seed-0001"), generate(b"provisioning-seed-0002"));
+        assert_eq!(generate(b"provisioning-seed-0001").chars().count(), GENERATED_MIN_LENGTH);
+    }
+
+    #[test]
+    fn short_seeds_are_rejected() {
+        assert!(generate_from_seed(b"too-short".to_vec(), PasswordPolicy::default()).is_err());
+    }
+
+    fn dob_context() -> UserContext {
+        UserContext { date_of_birth: Some("1990-07-05".to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn embedded_date_of_birth_is_flagged_in_several_formats() {
+        for password in ["mypass0705", "Qx!0507zzk", "Qx!1990zzk", "Qx!07051990", "Qx!05071990", "Qx!19900705", "Qx!070590k"] {
+            let analysis = check_password_policy_with_context(password.to_string(), None, dob_context()).unwrap();
+            assert!(analysis.pattern_analysis.contains_dob, "{:?} not flagged", password);
+            assert!(has_code(&analysis, "CONTAINS_DOB"));
+            assert!(!analysis.is_compliant);
+        }
+    }
+
+    #[test]
+    fn passwords_without_the_date_of_birth_are_not_flagged() {
+        let analysis = check_password_policy_with_context("Gx7!mRq2#vLp".to_string(), None, dob_context()).unwrap();
+        assert!(!analysis.pattern_analysis.contains_dob);
+        assert!(!check_password_policy("mypass0705".to_string(), None, None).unwrap().pattern_analysis.contains_dob);
+        assert_eq!(date_forms(1990, 7, 5)[0], "19900705");
+    }
+
+    fn bucketed() -> PasswordPolicy {
+        PasswordPolicy { bucket_length: Some(true), ..Default::default() }
+    }
+
+    #[test]
+    fn bucketed_length_reports_bucket_index_only() {
+        let exact = analyze_password("Gx7!mRq2#vLp", &PasswordPolicy::default(), &[]);
+        let analysis = analyze_password("Gx7!mRq2#vLp", &bucketed(), &[]);
+
+        assert_eq!(analysis.pattern_analysis.length, 2);
+        assert!(analysis.pattern_analysis.length_bucketed);
+        assert!(!exact.pattern_analysis.length_bucketed);
+        assert_eq!(analysis.strength_score, exact.strength_score);
+        assert_eq!(analysis.entropy_bits, exact.entropy_bits);
+        assert_eq!(analysis.is_compliant, exact.is_compliant);
+
+        let strict = PasswordPolicy { min_length: Some(10), ..bucketed() };
+        assert!(!has_code(&analyze_password("Gx7!mRq2#vLp", &strict, &[]), "TOO_SHORT"));
+    }
+
+    #[test]
+    fn length_buckets_split_at_documented_boundaries() {
+        let buckets: Vec<u32> = [0, 1, 7, 8, 11, 12, 15, 16, 200].into_iter().map(length_bucket_index).collect();
+        assert_eq!(buckets, vec![0, 0, 0, 1, 1, 2, 2, 3, 3]);
+    }
+
+    #[test]
+    fn consumers_honor_bucketed_lengths() {
+        for password in ["abc", "Gx7!mRq2", "Gx7!mRq2#vLp", "Tq9$wZe4!kNbXy7&"] {
+            let exact = telemetry_record(analyze_password(password, &PasswordPolicy::default(), &[]));
+            let record = telemetry_record(analyze_password(password, &bucketed(), &[]));
+            assert_eq!(record.length_bucket, exact.length_bucket, "{:?}", password);
+        }
+
+        let analysis = analyze_password("aaaaaaaaaaaa", &bucketed(), &[]);
+        assert_eq!(primary_weakness(&analysis, None).map(|(code, _)| code), Some("SINGLE_CHAR"));
+    }
+
+    #[test]
+    fn minimum_relaxation_uses_true_length_when_bucketed() {
+        let policy = PasswordPolicy { min_length: Some(16), bucket_length: Some(true), ..Default::default() };
+        let relaxation = minimum_relaxation("Gx7!mRq2#vLp".to_string(), policy);
+        assert_eq!(relaxation.relaxations[0].relaxed_value, 12.0);
+    }
+
+    #[test]
+    fn policy_columns_report_length_buckets_when_asked() {
+        let policy = PasswordPolicy { bucket_length: Some(true), ..Default::default() };
+        let columns = batch_policy_columns(vec!["abc".to_string(), "Tq9$wZe4!kNbXy7&".to_string()], policy);
+        assert_eq!(columns.length, vec![0, 3]);
+    }
+
+    fn timed_verify(password: &str, hash: &str, min_duration_ms: u32) -> (bool, Duration) {
+        let mut task = VerifyFixedTime {
+            password: password.to_string(),
+            hash: hash.to_string(),
+            min_duration: Duration::from_millis(min_duration_ms as u64),
+        };
+        let start_time = Instant::now();
+        let valid = task.compute().unwrap();
+        (valid, start_time.elapsed())
+    }
+
+    #[test]
+    fn fixed_time_verify_respects_floor_for_match_and_mismatch() {
+        let hash = hash_password_with_params("hunter2".to_string(), FAST_ARGON2, None).unwrap();
+        let floor = Duration::from_millis(300);
+
+        let (valid, elapsed) = timed_verify("hunter2", &hash, 300);
+        assert!(valid);
+        assert!(elapsed >= floor);
+
+        let (valid, elapsed) = timed_verify("hunter3", &hash, 300);
+        assert!(!valid);
+        assert!(elapsed >= floor);
+
+        let (valid, elapsed) = timed_verify("hunter2", "not a hash", 300);
+        assert!(!valid);
+        assert!(elapsed >= floor);
+    }
+
+    #[test]
+    fn fixed_time_verify_returns_once_verification_exceeds_envelope() {
+        let hash = hash_password_with_params("hunter2".to_string(), FAST_ARGON2, None).unwrap();
+        let (valid, elapsed) = timed_verify("hunter2", &hash, 0);
+        assert!(valid);
+        assert!(elapsed < Duration::from_millis(300));
+    }
+
+    #[test]
+    fn interleaved_incrementing_runs_are_detected_and_penalized() {
+        for password in ["a1b2c3", "1a2b3c", "3c2b1a", "Qx!d4e5f6g7"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert!(analysis.pattern_analysis.interleaved_sequence, "{:?} not detected", password);
+            assert!(analysis.effective_entropy_bits < analysis.entropy_bits, "{:?} not penalized", password);
+        }
+        assert_eq!(interleaved_sequence_len("Qx!d4e5f6g7"), Some(8));
+    }
+
+    #[test]
+    fn random_interleaving_of_same_length_is_not_a_sequence() {
+        for password in ["k7x2q9", "a1b2d4", "a1a1a1", "a1b3c5"] {
+            let analysis = analyze_password(password, &PasswordPolicy::default(), &[]);
+            assert!(!analysis.pattern_analysis.interleaved_sequence, "{:?} wrongly flagged", password);
+        }
+        let control = analyze_password("k7x2q9", &PasswordPolicy::default(), &[]);
+        assert_eq!(control.effective_entropy_bits, control.entropy_bits);
+    }
+
+    #[test]
+    fn sequence_span_scores_high_and_random_suffix_low() {
+        let values = char_guessability("abcX7#qZ9!".to_string());
+        assert_eq!(values.len(), 10);
+        assert!(values[..3].iter().all(|&value| value > 0.5), "{:?}", values);
+        assert!(values[3..].iter().all(|&value| value < 0.2), "{:?}", values);
+        assert!(values.iter().all(|value| (0.0..=1.0).contains(value)));
+    }
+
+    #[test]
+    fn guessability_is_per_grapheme() {
+        assert_eq!(char_guessability("ok👨\u{200D}👩\u{200D}👧e\u{301}".to_string()).len(), 4);
+        assert!(char_guessability(String::new()).is_empty());
+    }
+
+    #[test]
+    fn guessability_past_100_chars_is_unscored() {
+        let values = char_guessability("a".repeat(120));
+        assert_eq!(values.len(), 120);
+        assert!(values[..100].iter().all(|&value| value > 0.5), "{:?}", values);
+        assert!(values[100..].iter().all(|&value| value == 0.0), "{:?}", values);
+    }
+
+    fn distinct_from_previous(candidate: &str, previous: &[&str], min_distance: u32) -> bool {
+        let policy = PasswordPolicy { min_edit_distance_from_previous: Some(min_distance), ..Default::default() };
+        let previous = previous.iter().map(|p| p.to_string()).collect();
+        check_edit_distance_from_previous(candidate.to_string(), previous, policy).unwrap()
+    }
+
+    #[test]
+    fn edit_distance_threshold_of_one_only_blocks_exact_reuse() {
+        assert!(!distinct_from_previous("Summer2024!", &["Summer2024!"], 1));
+        assert!(distinct_from_previous("Summer2025!", &["Summer2024!"], 1));
+    }
+
+    #[test]
+    fn edit_distance_threshold_of_three_blocks_small_tweaks() {
+        let previous = ["Summer2024!", "Winter2023!"];
+        assert!(!distinct_from_previous("Summer2025!", &previous, 3));
+        assert!(!distinct_from_previous("Winter2023!!", &previous, 3));
+        assert!(!distinct_from_previous("Summer2025?", &previous, 3));
+        assert!(distinct_from_previous("Summer2121?", &previous, 3));
+        assert!(distinct_from_previous("anything", &[], 3));
+    }
+
+    #[test]
+    fn edit_distance_check_needs_the_policy_minimum() {
+        assert!(check_edit_distance_from_previous("x".to_string(), vec!["y".to_string()], PasswordPolicy::default()).is_err());
+    }
+
+    #[test]
+    fn repeated_words_count_once() {
+        let analysis = analyze_passphrase("apple apple apple orange".to_string(), None);
+        assert_eq!(analysis.word_count, 4);
+        assert_eq!(analysis.distinct_word_count, 2);
+        assert_eq!(analysis.repeated_words, vec!["apple"]);
+        assert_eq!(analysis.entropy_bits, 2.0 * PASSPHRASE_WORD_BITS);
+        assert!(!analysis.is_compliant);
+        let codes: Vec<&str> = analysis.feedback_items.iter().map(|item| item.code.as_str()).collect();
+        assert_eq!(codes, ["TOO_FEW_WORDS", "REPEATED_WORDS"]);
+    }
+
+    #[test]
+    fn distinct_passphrase_meets_min_words() {
+        let analysis = analyze_passphrase("Correct-horse_battery.staple".to_string(), None);
+        assert_eq!(analysis.distinct_word_count, 4);
+        assert!(analysis.is_compliant);
+        assert!(analysis.feedback_items.is_empty());
+
+        assert!(!analyze_passphrase("correct horse battery staple".to_string(), Some(5)).is_compliant);
+        assert_eq!(analyze_passphrase("Apple APPLE apple".to_string(), Some(1)).distinct_word_count, 1);
+    }
+
+    #[test]
+    fn uniform_distribution_uses_full_alphabet() {
+        assert!((effective_alphabet_size("abcdefgh".to_string()) - 8.0).abs() < 1e-9);
+        assert!((effective_alphabet_size("aabbccdd".to_string()) - 4.0).abs() < 1e-9);
+        assert_eq!(effective_alphabet_size("aaaa".to_string()), 1.0);
+        assert_eq!(effective_alphabet_size(String::new()), 0.0);
+    }
+
+    #[test]
+    fn skewed_distribution_has_small_effective_alphabet() {
+        let skewed = effective_alphabet_size("aaaab".to_string());
+        assert!((skewed - 1.649).abs() < 0.001, "{}", skewed);
+        assert!(skewed < effective_alphabet_size("abcde".to_string()));
+
+        let uniform = analyze_password("abcde", &PasswordPolicy::default(), &[]);
+        let lopsided = analyze_password("aaaab", &PasswordPolicy::default(), &[]);
+        assert_eq!(uniform.entropy_bits, lopsided.entropy_bits);
+        assert!(lopsided.distribution_entropy_bits < uniform.distribution_entropy_bits);
+        assert_eq!(analyze_password("aaaa", &PasswordPolicy::default(), &[]).distribution_entropy_bits, 0.0);
+    }
+
+    #[test]
+    fn emoji_password_under_both_unicode_flags() {
+        let password = "Gx7!mRq2#vLp🔥";
+        let encourage = PasswordPolicy { encourage_non_ascii: Some(true), ..Default::default() };
+        let ascii_only = PasswordPolicy { ascii_only: Some(true), ..Default::default() };
+
+        let encouraged = analyze_password(password, &encourage, &[]);
+        assert!(encouraged.pattern_analysis.has_non_ascii);
+        assert!(!has_code(&encouraged, "ADD_NON_ASCII"));
+        assert!(encouraged.is_compliant);
+
+        let restricted = analyze_password(password, &ascii_only, &[]);
+        assert!(has_code(&restricted, "NON_ASCII_FORBIDDEN"));
+        assert!(!restricted.is_compliant);
+    }
+
+    #[test]
+    fn ascii_password_under_both_unicode_flags() {
+        let encourage = PasswordPolicy { encourage_non_ascii: Some(true), ..Default::default() };
+        let ascii_only = PasswordPolicy { ascii_only: Some(true), ..Default::default() };
+
+        let encouraged = analyze_password("Gx7!mRq2#vLp", &encourage, &[]);
+        assert!(!encouraged.pattern_analysis.has_non_ascii);
+        let item = encouraged.feedback_items.iter().find(|item| item.code == "ADD_NON_ASCII").unwrap();
+        assert_eq!(item.severity, Severity::Info);
+        assert!(encouraged.is_compliant);
+
+        assert!(analyze_password("Gx7!mRq2#vLp", &ascii_only, &[]).is_compliant);
+        assert!(!has_code(&analyze_password("Gx7!mRq2#vLp", &PasswordPolicy::default(), &[]), "ADD_NON_ASCII"));
+    }
+
+    #[test]
+    fn reuse_clusters_group_shared_passwords() {
+        let passwords = ["Welcome1", "unique-a", "Summer24", "Welcome1", "unique-b", "Summer24", "Welcome1"].map(str::to_string).to_vec();
+        let clusters = find_reuse_clusters(passwords.clone());
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].member_indices, vec![0, 3, 6]);
+        assert_eq!(clusters[0].count, 3);
+        assert_eq!(clusters[1].member_indices, vec![2, 5]);
+        assert_eq!(clusters[1].count, 2);
+
+        let fingerprints = batch_fingerprint_sha256(passwords).unwrap().fingerprints;
+        assert_eq!(clusters[0].fingerprint, fingerprints[0]);
+        assert_eq!(clusters[1].fingerprint, fingerprints[2]);
+    }
+
+    #[test]
+    fn no_clusters_without_reuse() {
+        assert!(find_reuse_clusters(vec!["a".to_string(), "b".to_string(), "A".to_string()]).is_empty());
+        assert!(find_reuse_clusters(Vec::new()).is_empty());
+    }
+}